@@ -1,10 +1,11 @@
 use buji::*;
-use logy::LogController;
-use std::thread::sleep;
-use std::time::Duration;
+use logy::{ltrace, CrashReportConfig, LogController, LogLevel, WriterSink};
+use std::error::Error;
 
-fn main() -> Result<(), String> {
+fn main() -> Result<(), Box<dyn Error>> {
     LogController::init_logger();
+    LogController::add_sink(WriterSink::file("buji-client.log")?, LogLevel::Trace);
+    LogController::install_panic_hook(CrashReportConfig::new("buji-client-crash.log", 100));
 
     let my_game = MyGame::new();
     let mut buji = GameEngineBuilder::new()?
@@ -15,10 +16,11 @@ fn main() -> Result<(), String> {
         ))?
         .change_fps(DEFAULT_FPS)
         .add_game(my_game)
-        .add_asset_server("towerDefense_tilesheet.png", 32, 32, 4, 2)
+        .add_asset_server("towerDefense_tilesheet.png", 32, 32, 4, 2)?
         .build()?;
 
-    buji.run()
+    buji.run()?;
+    Ok(())
 }
 
 pub struct Player {
@@ -58,13 +60,26 @@ impl Default for MyGame {
 }
 
 impl GameObject for MyGame {
-    fn draw(&self, _asset_server: &AssetServer) {
-        sleep(Duration::from_millis(500));
-        println!("Draw operations...");
+    fn draw(&self, context: &mut RenderContext) -> Result<(), BujiError> {
+        let origin = Position::new(172, 268);
+        for index in 0..context.asset_server().tile_count() {
+            let position = Position::new(origin.x + index as i32 * 32, origin.y);
+            context.draw_tile(index, position, &TileParams::default())?;
+        }
+        context.draw_rect(origin, Scale2D::new(256, 32), &GameColor::from_rgb(WHITE))?;
+        context.draw_line(
+            Position::new(origin.x, origin.y + 40),
+            Position::new(origin.x + 256, origin.y + 40),
+            &GameColor::from_rgb(YELLOW),
+        )
     }
 
-    fn update(&mut self) -> MainState {
-        println!("Update operations...");
+    fn update(&mut self, context: &mut FrameContext) -> MainState {
+        ltrace!(
+            "Update operations... frame {} took {:?}",
+            context.frame,
+            context.delta
+        );
         MainState::Running
     }
 }
@@ -0,0 +1,611 @@
+use crate::input::{GamepadButton, Input, Keycode, MouseButton};
+use crate::BujiError;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+/**
+A single physical input that can trigger an action.
+
+Written as `Key:<SDL key name>`, `Mouse:<Left|Middle|Right|X1|X2|Unknown>` or
+`Gamepad:<SDL button name>` in configuration files, e.g. `Key:Space`, `Mouse:Left`, `Gamepad:a`.
+Keys without a name are written by their key code, e.g. `Key:#1073742106`.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    /// A keyboard key.
+    Key(Keycode),
+    /// A mouse button.
+    Mouse(MouseButton),
+    /// A button of any connected gamepad.
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    fn pressed(&self, input: &Input) -> bool {
+        match *self {
+            Binding::Key(key) => input.key_pressed(key),
+            Binding::Mouse(button) => input.mouse_pressed(button),
+            Binding::Gamepad(button) => input.gamepad_pressed(button),
+        }
+    }
+
+    fn held(&self, input: &Input) -> bool {
+        match *self {
+            Binding::Key(key) => input.key_held(key),
+            Binding::Mouse(button) => input.mouse_held(button),
+            Binding::Gamepad(button) => input.gamepad_held(button),
+        }
+    }
+
+    fn released(&self, input: &Input) -> bool {
+        match *self {
+            Binding::Key(key) => input.key_released(key),
+            Binding::Mouse(button) => input.mouse_released(button),
+            Binding::Gamepad(button) => input.gamepad_released(button),
+        }
+    }
+}
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Binding::Key(key) => {
+                let name = key.name();
+                if Keycode::from_name(&name) == Some(*key) {
+                    write!(f, "Key:{}", name)
+                } else {
+                    write!(f, "Key:#{}", key.into_i32())
+                }
+            }
+            Binding::Mouse(button) => {
+                let name = match button {
+                    MouseButton::Left => "Left",
+                    MouseButton::Middle => "Middle",
+                    MouseButton::Right => "Right",
+                    MouseButton::X1 => "X1",
+                    MouseButton::X2 => "X2",
+                    MouseButton::Unknown => "Unknown",
+                };
+                write!(f, "Mouse:{}", name)
+            }
+            Binding::Gamepad(button) => write!(f, "Gamepad:{}", button.string()),
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = BujiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || BujiError::InvalidConfig(format!("invalid binding '{}'", s));
+        let (kind, name) = s.split_once(':').ok_or_else(invalid)?;
+        match kind {
+            "Key" => match name.strip_prefix('#') {
+                Some(code) => code.parse().ok().and_then(Keycode::from_i32),
+                None => Keycode::from_name(name),
+            }
+            .map(Binding::Key),
+            "Mouse" => match name {
+                "Left" => Some(MouseButton::Left),
+                "Middle" => Some(MouseButton::Middle),
+                "Right" => Some(MouseButton::Right),
+                "X1" => Some(MouseButton::X1),
+                "X2" => Some(MouseButton::X2),
+                "Unknown" => Some(MouseButton::Unknown),
+                _ => None,
+            }
+            .map(Binding::Mouse),
+            "Gamepad" => GamepadButton::from_string(name).map(Binding::Gamepad),
+            _ => None,
+        }
+        .ok_or_else(invalid)
+    }
+}
+
+/// The bindings of an axis, which moves between -1 and 1.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AxisBindings {
+    /// Bindings that move the axis towards -1.
+    pub negative: Vec<Binding>,
+    /// Bindings that move the axis towards 1.
+    pub positive: Vec<Binding>,
+}
+
+/**
+Named actions and axes bound to keys, mouse buttons and gamepad buttons.
+
+Games query actions by name instead of key codes, so players can rebind controls.
+The engine keeps one in `GameEngine::actions`, reachable through `FrameContext`.
+
+# Example
+
+```rust
+use buji::{ActionMap, Binding, Keycode, MouseButton};
+
+let mut actions = ActionMap::new();
+actions
+    .bind("fire", Binding::Key(Keycode::Space))
+    .bind("fire", Binding::Mouse(MouseButton::Left))
+    .bind_axis("horizontal", Binding::Key(Keycode::Left), Binding::Key(Keycode::Right));
+
+let config = actions.to_config();
+assert_eq!(ActionMap::parse(&config).unwrap(), actions);
+```
+
+# Configuration file
+
+```text
+[actions]
+fire = Key:Space, Mouse:Left
+
+[axes]
+horizontal.negative = Key:Left
+horizontal.positive = Key:Right
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActionMap {
+    actions: BTreeMap<String, Vec<Binding>>,
+    axes: BTreeMap<String, AxisBindings>,
+}
+
+impl ActionMap {
+    /// Creates an empty action map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+    Adds a binding to an action. Adding the same binding twice has no effect.
+
+    # Arguments
+
+    * `action` - Name of the action, e.g. "fire".
+    * `binding` - The input that triggers the action.
+
+    # Returns
+
+    `&mut Self` - The action map for chaining.
+    */
+    pub fn bind(&mut self, action: &str, binding: Binding) -> &mut Self {
+        let bindings = self.actions.entry(action.to_string()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /**
+    Removes a binding from an action.
+
+    # Arguments
+
+    * `action` - Name of the action.
+    * `binding` - The input to remove.
+    */
+    pub fn unbind(&mut self, action: &str, binding: Binding) {
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|b| *b != binding);
+        }
+    }
+
+    /**
+    Replaces every binding of an action, e.g. after the player picked a new key.
+
+    # Arguments
+
+    * `action` - Name of the action.
+    * `bindings` - The new bindings.
+    */
+    pub fn rebind(&mut self, action: &str, bindings: Vec<Binding>) {
+        self.actions.insert(action.to_string(), bindings);
+    }
+
+    /**
+    Returns the bindings of an action.
+
+    # Arguments
+
+    * `action` - Name of the action.
+
+    # Returns
+
+    `&[Binding]` - The bindings, empty if the action is unknown.
+    */
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /**
+    Adds a binding to each direction of an axis.
+
+    # Arguments
+
+    * `axis` - Name of the axis, e.g. "horizontal".
+    * `negative` - The input that moves the axis towards -1.
+    * `positive` - The input that moves the axis towards 1.
+
+    # Returns
+
+    `&mut Self` - The action map for chaining.
+    */
+    pub fn bind_axis(&mut self, axis: &str, negative: Binding, positive: Binding) -> &mut Self {
+        let bindings = self.axes.entry(axis.to_string()).or_default();
+        if !bindings.negative.contains(&negative) {
+            bindings.negative.push(negative);
+        }
+        if !bindings.positive.contains(&positive) {
+            bindings.positive.push(positive);
+        }
+        self
+    }
+
+    /**
+    Replaces every binding of an axis.
+
+    # Arguments
+
+    * `axis` - Name of the axis.
+    * `bindings` - The new bindings.
+    */
+    pub fn rebind_axis(&mut self, axis: &str, bindings: AxisBindings) {
+        self.axes.insert(axis.to_string(), bindings);
+    }
+
+    /**
+    Returns the bindings of an axis.
+
+    # Arguments
+
+    * `axis` - Name of the axis.
+    */
+    pub fn axis_bindings(&self, axis: &str) -> Option<&AxisBindings> {
+        self.axes.get(axis)
+    }
+
+    /**
+    Checks whether any binding of an action went down during this frame.
+
+    # Arguments
+
+    * `input` - The input state of the frame.
+    * `action` - Name of the action.
+    */
+    pub fn pressed(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| b.pressed(input))
+    }
+
+    /**
+    Checks whether any binding of an action is currently down.
+
+    # Arguments
+
+    * `input` - The input state of the frame.
+    * `action` - Name of the action.
+    */
+    pub fn held(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| b.held(input))
+    }
+
+    /**
+    Checks whether a binding of an action went up during this frame.
+
+    # Arguments
+
+    * `input` - The input state of the frame.
+    * `action` - Name of the action.
+    */
+    pub fn released(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| b.released(input))
+    }
+
+    /**
+    Returns the value of an axis.
+
+    # Arguments
+
+    * `input` - The input state of the frame.
+    * `axis` - Name of the axis.
+
+    # Returns
+
+    `f32` - -1 if only a negative binding is held, 1 if only a positive one is held, 0 otherwise.
+    */
+    pub fn axis(&self, input: &Input, axis: &str) -> f32 {
+        let Some(bindings) = self.axes.get(axis) else {
+            return 0.0;
+        };
+        let negative = bindings.negative.iter().any(|b| b.held(input));
+        let positive = bindings.positive.iter().any(|b| b.held(input));
+        match (negative, positive) {
+            (true, false) => -1.0,
+            (false, true) => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /**
+    Parses an action map from the configuration format.
+
+    # Arguments
+
+    * `config` - The configuration text.
+
+    # Errors
+
+    Returns `BujiError::InvalidConfig` if a line or a binding cannot be parsed.
+    */
+    pub fn parse(config: &str) -> Result<Self, BujiError> {
+        let mut map = ActionMap::new();
+        let mut section = "";
+
+        for (number, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = match name {
+                    "actions" => "actions",
+                    "axes" => "axes",
+                    _ => {
+                        return Err(BujiError::InvalidConfig(format!(
+                            "unknown section '{}' on line {}",
+                            name,
+                            number + 1
+                        )))
+                    }
+                };
+                continue;
+            }
+
+            let invalid_line =
+                || BujiError::InvalidConfig(format!("invalid line {}: '{}'", number + 1, line));
+            let (name, value) = line.split_once('=').ok_or_else(invalid_line)?;
+            let name = name.trim();
+            let bindings = split_bindings(value)
+                .into_iter()
+                .map(|binding| {
+                    Binding::from_str(binding).map_err(|_| {
+                        BujiError::InvalidConfig(format!(
+                            "invalid binding '{}' on line {}",
+                            binding,
+                            number + 1
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            match section {
+                "actions" => map.rebind(name, bindings),
+                "axes" => {
+                    let axis = match name.rsplit_once('.') {
+                        Some((axis, "negative")) => {
+                            &mut map.axes.entry(axis.to_string()).or_default().negative
+                        }
+                        Some((axis, "positive")) => {
+                            &mut map.axes.entry(axis.to_string()).or_default().positive
+                        }
+                        _ => return Err(invalid_line()),
+                    };
+                    *axis = bindings;
+                }
+                _ => return Err(invalid_line()),
+            }
+        }
+
+        Ok(map)
+    }
+
+    /**
+    Writes the action map in the configuration format.
+
+    # Returns
+
+    `String` - The configuration text, readable by `ActionMap::parse`.
+    */
+    pub fn to_config(&self) -> String {
+        let join = |bindings: &[Binding]| {
+            bindings
+                .iter()
+                .map(Binding::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let mut config = String::from("[actions]\n");
+        for (action, bindings) in &self.actions {
+            config.push_str(&format!("{} = {}\n", action, join(bindings)));
+        }
+        config.push_str("\n[axes]\n");
+        for (axis, bindings) in &self.axes {
+            config.push_str(&format!(
+                "{}.negative = {}\n",
+                axis,
+                join(&bindings.negative)
+            ));
+            config.push_str(&format!(
+                "{}.positive = {}\n",
+                axis,
+                join(&bindings.positive)
+            ));
+        }
+        config
+    }
+
+    /**
+    Loads an action map from a configuration file.
+
+    # Arguments
+
+    * `path` - Path of the configuration file.
+
+    # Errors
+
+    Returns `BujiError::ConfigIo` if the file cannot be read and
+    `BujiError::InvalidConfig` if it cannot be parsed.
+    */
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, BujiError> {
+        let path = path.as_ref();
+        let config = std::fs::read_to_string(path).map_err(|source| BujiError::ConfigIo {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&config)
+    }
+
+    /**
+    Saves the action map to a configuration file, replacing its content.
+
+    # Arguments
+
+    * `path` - Path of the configuration file.
+
+    # Errors
+
+    Returns `BujiError::ConfigIo` if the file cannot be written.
+    */
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), BujiError> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_config()).map_err(|source| BujiError::ConfigIo {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// Splits a comma separated list of bindings.
+///
+/// A comma only separates bindings if the next one starts right after it, so the comma
+/// key (`Key:,`) and `Key:Keypad ,` can be written as they are.
+fn split_bindings(value: &str) -> Vec<&str> {
+    let starts_binding = |rest: &str| {
+        let rest = rest.trim_start();
+        ["Key:", "Mouse:", "Gamepad:"]
+            .iter()
+            .any(|kind| rest.starts_with(kind))
+    };
+    let mut bindings = Vec::new();
+    let mut start = 0;
+    for (index, _) in value.match_indices(',') {
+        if starts_binding(&value[index + 1..]) {
+            bindings.push(value[start..index].trim());
+            start = index + 1;
+        }
+    }
+    bindings.push(value[start..].trim());
+    bindings.retain(|binding| !binding.is_empty());
+    bindings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ActionMap {
+        let mut actions = ActionMap::new();
+        actions
+            .bind("fire", Binding::Key(Keycode::Space))
+            .bind("fire", Binding::Mouse(MouseButton::Left))
+            .bind("fire", Binding::Gamepad(GamepadButton::A))
+            .bind("menu", Binding::Key(Keycode::Comma))
+            .bind("menu", Binding::Mouse(MouseButton::Unknown))
+            .bind_axis(
+                "horizontal",
+                Binding::Key(Keycode::Left),
+                Binding::Key(Keycode::Right),
+            );
+        actions
+    }
+
+    #[test]
+    fn parse_accepts_commas_with_any_spacing() {
+        let actions = ActionMap::parse(
+            "[actions]\nfire = Key:Space,Mouse:Left ,  Gamepad:a\nmenu = Key:,\nback = Key:,,Key:Escape\n",
+        )
+        .unwrap();
+        assert_eq!(
+            actions.bindings("fire"),
+            [
+                Binding::Key(Keycode::Space),
+                Binding::Mouse(MouseButton::Left),
+                Binding::Gamepad(GamepadButton::A)
+            ]
+        );
+        assert_eq!(actions.bindings("menu"), [Binding::Key(Keycode::Comma)]);
+        assert_eq!(
+            actions.bindings("back"),
+            [Binding::Key(Keycode::Comma), Binding::Key(Keycode::Escape)]
+        );
+    }
+
+    #[test]
+    fn parse_reads_axes_and_skips_comments() {
+        let actions = ActionMap::parse(
+            "# controls\n[axes]\nhorizontal.negative = Key:Left\nhorizontal.positive = Key:Right, Gamepad:dpright\n",
+        )
+        .unwrap();
+        let axis = actions.axis_bindings("horizontal").unwrap();
+        assert_eq!(axis.negative, [Binding::Key(Keycode::Left)]);
+        assert_eq!(
+            axis.positive,
+            [
+                Binding::Key(Keycode::Right),
+                Binding::Gamepad(GamepadButton::DPadRight)
+            ]
+        );
+    }
+
+    #[test]
+    fn to_config_round_trips() {
+        let actions = sample();
+        assert_eq!(ActionMap::parse(&actions.to_config()).unwrap(), actions);
+    }
+
+    #[test]
+    fn every_binding_display_parses_back() {
+        let bindings = [
+            Binding::Key(Keycode::KpComma),
+            Binding::Key(Keycode::from_i32(0x7fff_0000).unwrap()),
+            Binding::Mouse(MouseButton::Unknown),
+            Binding::Mouse(MouseButton::X2),
+            Binding::Gamepad(GamepadButton::Paddle1),
+        ];
+        for binding in bindings {
+            assert_eq!(binding.to_string().parse::<Binding>().unwrap(), binding);
+        }
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        let error = |config: &str| ActionMap::parse(config).unwrap_err().to_string();
+        assert!(error("[actions]\nfire = Key:Space\n[keys]\n").contains("line 3"));
+        assert!(error("[actions]\n\nfire Key:Space\n").contains("invalid line 3"));
+        assert_eq!(
+            error("[actions]\nfire = Key:NoSuchKey\n"),
+            "Invalid configuration : invalid binding 'Key:NoSuchKey' on line 2"
+        );
+        assert!(error("[axes]\nhorizontal.up = Key:Up\n").contains("invalid line 2"));
+        assert!(error("fire = Key:Space\n").contains("invalid line 1"));
+    }
+
+    #[test]
+    fn save_and_load_through_a_file() {
+        let path = std::env::temp_dir().join(format!("buji-actions-{}.cfg", std::process::id()));
+        let actions = sample();
+
+        actions.save(&path).unwrap();
+        let loaded = ActionMap::load(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.unwrap(), actions);
+    }
+
+    #[test]
+    fn load_reports_missing_file() {
+        let result = ActionMap::load("does/not/exist.cfg");
+        assert!(matches!(result, Err(BujiError::ConfigIo { .. })));
+    }
+}
@@ -1,18 +1,23 @@
+use crate::BujiError;
 use image::*;
 use logy::*;
-use std::collections::HashMap;
-use std::io::Cursor;
+use sdl2::rect::Rect;
+use std::path::Path;
 
 /// A simple asset server struct for handling sprite sheet loading.
 #[derive(Default)]
 pub struct AssetServer {
-    /// Vector based tile map of sprite sheet
-    pub tile_map: HashMap<usize, Vec<u8>>,
+    /// Decoded sprite sheet, uploaded to the GPU once by the `TextureManager`
+    sprite_sheet: Option<RgbaImage>,
+    /// Source rectangle of each tile in the sprite sheet, by index
+    tiles: Vec<Rect>,
 }
 
 impl AssetServer {
     /**
-    Loads a sprite sheet from the specified source path and splits it into individual tiles.
+    Loads a sprite sheet from the specified source path and computes the source rectangle of each tile.
+
+    Tiles are indexed row by row, starting at zero. Tiles that do not fit into the image entirely are skipped.
 
     # Arguments
 
@@ -22,58 +27,122 @@ impl AssetServer {
     * `columns` - The number of columns to load.
     * `rows` - The number of rows to load.
 
-    # Panics
+    # Errors
 
-    This function will panic if the image cannot be opened or if there is an error writing the tile data.
+    Returns `BujiError::InvalidConfig` if a tile size is zero, `BujiError::AssetIo` if the
+    image cannot be read and `BujiError::ImageDecode` if it cannot be decoded.
     */
-    pub fn init(
+    pub fn init<P: AsRef<Path>>(
         &mut self,
-        source_path: &str,
+        source_path: P,
         tile_width: u32,
         tile_height: u32,
         columns: u32,
         rows: u32,
-    ) {
-        linfo!(LogLevel::Info, "Initializing AssetServer");
-        linfo!(LogLevel::Warn, &format!("source_path: {}", source_path));
-
-        let img = open(source_path).expect("Failed to open image");
-        let (w, h) = img.dimensions();
-
-        let max_width = columns * tile_width;
-        let max_height = rows * tile_height;
+    ) -> Result<(), BujiError> {
+        let source_path = source_path.as_ref();
+        linfo!("Initializing AssetServer");
+        lwarn!("source_path: {}", source_path.display());
+
+        let result = self.load(source_path, tile_width, tile_height, columns, rows);
+        if let Err(e) = &result {
+            lwarn!("Failed to initialize AssetServer : {}", e);
+        }
+        result
+    }
 
-        let final_width = max_width.min(w);
-        let final_height = max_height.min(h);
+    fn load(
+        &mut self,
+        source_path: &Path,
+        tile_width: u32,
+        tile_height: u32,
+        columns: u32,
+        rows: u32,
+    ) -> Result<(), BujiError> {
+        if tile_width == 0 || tile_height == 0 {
+            return Err(BujiError::InvalidConfig(format!(
+                "tile size must not be zero, got {}x{}",
+                tile_width, tile_height
+            )));
+        }
 
-        let mut index = 0;
+        let img = open(source_path)
+            .map_err(|e| BujiError::from_image(source_path, e))?
+            .to_rgba8();
+        let (w, h) = img.dimensions();
 
-        for y in (0..final_height).step_by(tile_height as usize) {
-            for x in (0..final_width).step_by(tile_width as usize) {
-                let tile = img.view(x, y, tile_width, tile_height).to_image();
-                let mut tile_bytes = Vec::new();
-                let mut cursor = Cursor::new(&mut tile_bytes);
-                tile.write_to(&mut cursor, image::ImageFormat::Png)
-                    .expect("Write error");
-                self.tile_map.insert(index, tile_bytes);
-                index += 1;
-            }
-        }
+        let columns = columns.min(w / tile_width);
+        let rows = rows.min(h / tile_height);
+
+        self.tiles = (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| {
+                    Rect::new(
+                        (column * tile_width) as i32,
+                        (row * tile_height) as i32,
+                        tile_width,
+                        tile_height,
+                    )
+                })
+            })
+            .collect();
+        self.sprite_sheet = Some(img);
+
+        Ok(())
     }
 
     /**
 
-    Loads a texture from the tile map store
+    Returns the source rectangle of a tile in the sprite sheet
 
     # Arguments
 
-    * `index` - Index number of texture
+    * `index` - Index number of tile
 
     # Returns
 
-    `Option<Vec<u8>>` - Returns byte array of texture
+    `Option<Rect>` - Returns the position and size of the tile, or `None` if it does not exist
     */
-    pub fn get_texture(&self, index: usize) -> Option<&Vec<u8>> {
-        self.tile_map.get(&index)
+    pub fn tile_rect(&self, index: usize) -> Option<Rect> {
+        self.tiles.get(index).copied()
+    }
+
+    /// Returns the number of tiles in the sprite sheet.
+    pub fn tile_count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Returns the decoded sprite sheet, if one has been loaded.
+    pub fn sprite_sheet(&self) -> Option<&RgbaImage> {
+        self.sprite_sheet.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_warns_about_zero_tile_size() {
+        let _capture = LogCapture::start();
+        let mut asset_server = AssetServer::default();
+
+        let result = asset_server.init("assets/sprites.png", 0, 16, 4, 4);
+
+        assert!(matches!(result, Err(BujiError::InvalidConfig(_))));
+        assert_logged!(LogLevel::Warn, contains "tile size must not be zero");
+        assert_eq!(asset_server.tile_count(), 0);
+    }
+
+    #[test]
+    fn init_warns_about_missing_file() {
+        let _capture = LogCapture::start();
+        let mut asset_server = AssetServer::default();
+
+        let result = asset_server.init("does/not/exist.png", 16, 16, 4, 4);
+
+        assert!(matches!(result, Err(BujiError::AssetIo { .. })));
+        assert_logged!(LogLevel::Warn, contains "Failed to initialize AssetServer");
+        assert!(asset_server.sprite_sheet().is_none());
     }
 }
@@ -1,5 +1,8 @@
 pub const NANOS_PER_SECOND: u32 = 1_000_000_000;
 pub const DEFAULT_FPS: u32 = 60;
+pub const MAX_FIXED_STEPS: u32 = 5;
+pub const DEFAULT_STICK_DEAD_ZONE: f32 = 0.2;
+pub const DEFAULT_TRIGGER_DEAD_ZONE: f32 = 0.05;
 pub const BLACK: [u8; 3] = [0, 0, 0];
 pub const WHITE: [u8; 3] = [255, 255, 255];
 pub const RED: [u8; 3] = [255, 0, 0];
@@ -9,5 +12,5 @@ pub const YELLOW: [u8; 3] = [255, 255, 0];
 pub const MAGENTA: [u8; 3] = [255, 0, 255];
 pub const CYAN: [u8; 3] = [0, 255, 255];
 pub const SILVER: [u8; 3] = [192, 192, 192];
-pub const NAVY: [u8; 3] = [0, 255, 128];
+pub const NAVY: [u8; 3] = [0, 0, 128];
 pub const PURPLE: [u8; 3] = [128, 0, 128];
@@ -1,6 +1,11 @@
+use crate::action::ActionMap;
 use crate::asset_server::AssetServer;
+use crate::frame::{FixedStep, FrameContext};
+use crate::input::Input;
+use crate::render::RenderContext;
+use crate::texture::TextureManager;
 use crate::world::World;
-use crate::{GameWindow, DEFAULT_FPS, NANOS_PER_SECOND};
+use crate::{BujiError, GameWindow, DEFAULT_FPS, MAX_FIXED_STEPS, NANOS_PER_SECOND};
 use logy::*;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
@@ -9,6 +14,7 @@ use std::thread::sleep;
 use std::time::{Duration, Instant};
 
 /// Enum representing the main states of the game engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MainState {
     /// Initial state, where the game has not yet started.
     Init,
@@ -23,22 +29,49 @@ pub enum MainState {
 /// A trait representing a game object. This must be implemented by and game object.
 pub trait GameObject {
     /**
-    Draw operations. Called every frame.
+    Draw operations. Called every frame, after `update` and `fixed_update`.
 
     # Arguments
 
-    * `asset_server` - Reference of asset server
+    * `context` - Drawing operations on the window canvas, the asset server and
+      the interpolation alpha of the fixed update mode
+
+    # Errors
+
+    Errors are logged by the engine, the game keeps running.
     */
-    fn draw(&self, asset_server: &AssetServer);
+    fn draw(&self, context: &mut RenderContext) -> Result<(), BujiError>;
     /**
     Update method for game actors. This is called every frame and
     should return the next state of main engine.
 
+    # Arguments
+
+    * `context` - Delta time, elapsed time, frame number, input and actions of this frame
+
+    # Returns
+
+    A `MainState` value indicating the next state of the engine.
+    */
+    fn update(&mut self, context: &mut FrameContext) -> MainState;
+    /**
+    Simulation step with a constant delta time. Only called when the fixed update mode
+    is enabled, zero or more times per frame after `update`.
+
+    Input events, and keys or buttons pressed or released, are reported to the next
+    fixed update exactly once, even if they happened during frames that ran no fixed update.
+
+    # Arguments
+
+    * `context` - Fixed delta time, simulated time, step number, input and actions
+
     # Returns
 
     A `MainState` value indicating the next state of the engine.
     */
-    fn update(&mut self) -> MainState;
+    fn fixed_update(&mut self, _context: &mut FrameContext) -> MainState {
+        MainState::Running
+    }
 }
 
 /// Game Engine, responsible for managing the game loop.
@@ -47,12 +80,23 @@ pub struct GameEngine {
     pub game_object: Option<Box<dyn GameObject>>,
     /// Frames per second value for the game
     pub fps: u32,
+    /// Rate of `GameObject::fixed_update` calls per second. Fixed update mode is disabled if `None`.
+    pub fixed_update_rate: Option<u32>,
+    /// Maximum number of fixed updates per frame. Simulation time beyond this is dropped
+    /// so that a slow frame cannot make the next one even slower.
+    pub max_fixed_steps: u32,
     /// Main screen object of the game
     pub window: GameWindow,
     /// Asset manager of the game
     pub asset_server: AssetServer,
     /// World object to manage all game figures
     pub world: World<'static>,
+    /// Keyboard and mouse state, updated every frame
+    pub input: Input,
+    /// Named actions and axes that games query instead of raw inputs
+    pub actions: ActionMap,
+    /// Whether pressing Escape exits the game
+    pub escape_quits: bool,
 }
 
 impl Default for GameEngine {
@@ -60,9 +104,14 @@ impl Default for GameEngine {
         Self {
             window: GameWindow::default(),
             fps: DEFAULT_FPS,
+            fixed_update_rate: None,
+            max_fixed_steps: MAX_FIXED_STEPS,
             game_object: None,
             asset_server: AssetServer::default(),
             world: World::default(),
+            input: Input::default(),
+            actions: ActionMap::default(),
+            escape_quits: true,
         }
     }
 }
@@ -74,71 +123,183 @@ impl GameEngine {
 
     # Returns
 
-    `Result<(), String>` - Returns `Ok(())` if the main loop exists successfully,
-    or an error message if something goes wrong.
+    `Result<(), BujiError>` - Returns `Ok(())` if the main loop exists successfully,
+    or an error if something goes wrong.
+
+    # Errors
+
+    Returns `BujiError::InvalidConfig` if the FPS, fixed update rate or maximum number of
+    fixed steps is zero, the error of `GameWindow::init` if the window cannot be initialized,
+    or `BujiError::Render` if the sprite sheet cannot be uploaded to a texture.
     */
-    pub fn run(&mut self) -> Result<(), String> {
+    pub fn run(&mut self) -> Result<(), BujiError> {
+        self.validate()?;
         self.window.init()?;
-        linfo!(LogLevel::Info, "Initializing the game engine");
+        linfo!("Initializing the game engine");
 
         let mut state = MainState::Init;
-        let mut last_update = Instant::now();
+        let mut frame: u64 = 0;
+        let mut started = Instant::now();
+        let mut last_frame_start = Instant::now();
         let frame_duration = Duration::new(0, NANOS_PER_SECOND / self.fps);
-
-        let mut event_pump = self.window.sdl_context.as_ref().unwrap().event_pump()?;
+        let mut fixed_step = self.fixed_update_rate.map(|rate| {
+            FixedStep::new(
+                Duration::new(0, NANOS_PER_SECOND / rate),
+                self.max_fixed_steps,
+            )
+        });
+        let mut simulated = Duration::ZERO;
+        let mut fixed_frame: u64 = 0;
+        // Events and pressed/released edges wait here until a fixed update consumes them.
+        let mut fixed_events: Vec<Event> = Vec::new();
+        let mut fixed_input = self.input.clone();
+
+        let mut event_pump = self
+            .window
+            .sdl_context
+            .as_ref()
+            .ok_or_else(|| BujiError::VideoInit("SDL context is not initialized".to_string()))?
+            .event_pump()
+            .map_err(BujiError::VideoInit)?;
+
+        let texture_creator = self
+            .window
+            .canvas
+            .as_ref()
+            .ok_or_else(|| BujiError::WindowCreation("canvas is not initialized".to_string()))?
+            .texture_creator();
+        let textures = TextureManager::new(&texture_creator, &self.asset_server)?;
 
         loop {
-            for event in event_pump.poll_iter() {
+            let _context = lcontext!(frame = frame, state = format!("{:?}", state));
+            let _frame_span = lspan!("frame");
+            let frame_start = Instant::now();
+            let delta = frame_start.duration_since(last_frame_start);
+            last_frame_start = frame_start;
+
+            let poll_span = lspan!("poll_events");
+            let events: Vec<Event> = event_pump.poll_iter().collect();
+            self.input.begin_frame();
+            for event in &events {
+                self.input.handle_event(event);
+                if fixed_step.is_some() {
+                    fixed_input.handle_event(event);
+                }
                 match event {
                     Event::Quit { .. } => {
-                        linfo!(LogLevel::Warn, "Quit event received. Exiting...");
+                        lwarn!("Quit event received. Exiting...");
                         state = MainState::PreExit;
                     }
                     Event::KeyDown {
                         keycode: Some(Keycode::Escape),
                         ..
-                    } => {
-                        linfo!(LogLevel::Warn, "Escaped key pressed. Exiting...");
+                    } if self.escape_quits => {
+                        lwarn!("Escaped key pressed. Exiting...");
                         state = MainState::PreExit;
                     }
+                    Event::ControllerDeviceAdded { which, .. } => {
+                        if let Some(subsystem) = &self.window.game_controller {
+                            if let Some(gamepad) = self.input.connect_gamepad(subsystem, *which) {
+                                fixed_input.add_gamepad(gamepad.clone());
+                            }
+                        }
+                    }
                     _ => {}
                 }
             }
+            drop(poll_span);
 
             match state {
                 MainState::Init => {
+                    started = Instant::now();
                     state = MainState::Running;
-                    linfo!(LogLevel::Info, "Going to Running state");
+                    linfo!("Going to Running state");
                     continue;
                 }
                 MainState::Running => {
-                    linfo!(LogLevel::Info, "On Running state");
+                    ltrace!("On Running state");
 
-                    let now = Instant::now();
-                    let delta = now.duration_since(last_update);
+                    let mut context = FrameContext::new(
+                        delta,
+                        frame_start.duration_since(started),
+                        frame,
+                        &events,
+                        &self.input,
+                        &mut self.actions,
+                    );
 
                     self.window.cleanup();
 
                     if let Some(game_object) = &mut self.game_object {
-                        game_object.draw(&self.asset_server);
-                        state = game_object.update();
+                        {
+                            let _span = lspan!("update");
+                            state = game_object.update(&mut context);
+                        }
+
+                        let mut alpha = 1.0;
+                        if let Some(fixed_step) = &mut fixed_step {
+                            let _span = lspan!("fixed_update");
+                            let step = fixed_step.step();
+                            let steps = fixed_step.accumulate(delta);
+
+                            fixed_events.extend(events.iter().cloned());
+                            for _ in 0..steps {
+                                if state != MainState::Running {
+                                    break;
+                                }
+                                let mut fixed_context = FrameContext::new(
+                                    step,
+                                    simulated,
+                                    fixed_frame,
+                                    &fixed_events,
+                                    &fixed_input,
+                                    &mut self.actions,
+                                );
+                                state = game_object.fixed_update(&mut fixed_context);
+                                fixed_events.clear();
+                                fixed_input.begin_frame();
+                                simulated += step;
+                                fixed_frame += 1;
+                            }
+                            alpha = fixed_step.alpha();
+                        }
+
+                        let _span = lspan!("draw");
+                        if let Some(canvas) = self.window.canvas.as_mut() {
+                            let mut render_context = RenderContext::new(
+                                canvas,
+                                &self.window.scale2d,
+                                &self.asset_server,
+                                &textures,
+                                alpha,
+                            );
+                            if let Err(e) = game_object.draw(&mut render_context) {
+                                lerror!("Failed to draw : {}", e);
+                            }
+                        }
                     }
 
-                    self.window.present();
+                    {
+                        let _span = lspan!("present");
+                        self.window.present();
+                    }
 
-                    if frame_duration > delta {
-                        sleep(frame_duration - delta);
+                    let work = frame_start.elapsed();
+                    if frame_duration > work {
+                        let _span = lspan!("sleep");
+                        sleep(frame_duration - work);
                     }
 
-                    last_update = now;
+                    frame += 1;
                 }
                 MainState::PreExit => {
-                    linfo!(LogLevel::Warn, "Pre Exit...");
+                    lwarn!("Pre Exit...");
                     state = MainState::Exit;
                     continue;
                 }
                 MainState::Exit => {
-                    linfo!(LogLevel::Warn, "Exiting from game engine");
+                    lwarn!("Exiting from game engine");
+                    LogController::shutdown();
                     break;
                 }
             }
@@ -146,6 +307,24 @@ impl GameEngine {
 
         Ok(())
     }
+
+    /// Checks the engine settings that would otherwise make the main loop panic.
+    fn validate(&self) -> Result<(), BujiError> {
+        if self.fps == 0 {
+            return Err(BujiError::InvalidConfig("FPS must not be zero".to_string()));
+        }
+        if self.fixed_update_rate == Some(0) {
+            return Err(BujiError::InvalidConfig(
+                "fixed update rate must not be zero".to_string(),
+            ));
+        }
+        if self.max_fixed_steps == 0 {
+            return Err(BujiError::InvalidConfig(
+                "maximum number of fixed steps must not be zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 /**
@@ -155,24 +334,23 @@ up the game window, FPS, and the game object in a more flexible and readable way
 # Example
 
 ```rust
-use buji::{GameObject, MainState, GameEngineBuilder, DEFAULT_FPS};
-use std::io::{stdout, Write};
-use buji::AssetServer;
+use buji::{BujiError, FrameContext, GameObject, MainState, GameEngineBuilder, RenderContext, DEFAULT_FPS};
 
 struct YourGameObject;
 
 impl GameObject for YourGameObject {
-     fn draw(&self,asset_server: &AssetServer) {
+     fn draw(&self, context: &mut RenderContext) -> Result<(), BujiError> {
          // Draw game objects here
+         Ok(())
      }
 
-     fn update(&mut self) -> MainState {
+     fn update(&mut self, context: &mut FrameContext) -> MainState {
          // Update game objects and return the next state
          MainState::Running
      }
 }
 
-fn main() -> Result<(), String> {
+fn main() -> Result<(), BujiError> {
 
      let game = YourGameObject;
      let mut engine = GameEngineBuilder::new()?
@@ -198,10 +376,10 @@ impl GameEngineBuilder {
 
     # Returns
 
-    `Result<Self, String>` - Returns a new `GameEngineBuilder` instance
-    if successful or an error message if something goes wrong.
+    `Result<Self, BujiError>` - Returns a new `GameEngineBuilder` instance
+    if successful or an error if something goes wrong.
     */
-    pub fn new() -> Result<Self, String> {
+    pub fn new() -> Result<Self, BujiError> {
         Ok(Self {
             game_engine: GameEngine::default(),
         })
@@ -216,9 +394,9 @@ impl GameEngineBuilder {
 
     # Returns
 
-    `Result<Self, String>` - Returns the `GameEngineBuilder` instance for chaining.
+    `Result<Self, BujiError>` - Returns the `GameEngineBuilder` instance for chaining.
     */
-    pub fn setup_window(mut self, window: GameWindow) -> Result<Self, String> {
+    pub fn setup_window(mut self, window: GameWindow) -> Result<Self, BujiError> {
         self.game_engine.window = window;
         Ok(self)
     }
@@ -239,6 +417,88 @@ impl GameEngineBuilder {
         self
     }
 
+    /**
+    Enables the fixed update mode. `GameObject::fixed_update` is then called at a constant
+    rate, independent of the render FPS.
+
+    # Arguments
+
+    * `rate` - The number of fixed updates per second, e.g. 60.
+
+    # Returns
+
+    `Self` - Returns the `GameEngineBuilder` instance for chaining.
+    */
+    pub fn fixed_update_rate(mut self, rate: u32) -> Self {
+        self.game_engine.fixed_update_rate = Some(rate);
+        self
+    }
+
+    /**
+    Sets the maximum number of fixed updates per frame. Defaults to `MAX_FIXED_STEPS`.
+
+    # Arguments
+
+    * `steps` - The maximum number of fixed updates run in a single frame.
+
+    # Returns
+
+    `Self` - Returns the `GameEngineBuilder` instance for chaining.
+    */
+    pub fn max_fixed_steps(mut self, steps: u32) -> Self {
+        self.game_engine.max_fixed_steps = steps;
+        self
+    }
+
+    /**
+    Enables or disables exiting the game when Escape is pressed. Enabled by default.
+
+    # Arguments
+
+    * `enabled` - `false` to handle the Escape key in the game object instead.
+
+    # Returns
+
+    `Self` - Returns the `GameEngineBuilder` instance for chaining.
+    */
+    pub fn escape_quits(mut self, enabled: bool) -> Self {
+        self.game_engine.escape_quits = enabled;
+        self
+    }
+
+    /**
+    Sets the named actions and axes, e.g. loaded with `ActionMap::load`.
+
+    # Arguments
+
+    * `actions` - The action map queried through `FrameContext`.
+
+    # Returns
+
+    `Self` - Returns the `GameEngineBuilder` instance for chaining.
+    */
+    pub fn action_map(mut self, actions: ActionMap) -> Self {
+        self.game_engine.actions = actions;
+        self
+    }
+
+    /**
+    Sets the gamepad dead zones. Defaults to `DEFAULT_STICK_DEAD_ZONE` and `DEFAULT_TRIGGER_DEAD_ZONE`.
+
+    # Arguments
+
+    * `stick` - Stick movements smaller than this (0..1) are reported as zero.
+    * `trigger` - Trigger values smaller than this (0..1) are reported as zero.
+
+    # Returns
+
+    `Self` - Returns the `GameEngineBuilder` instance for chaining.
+    */
+    pub fn gamepad_dead_zones(mut self, stick: f32, trigger: f32) -> Self {
+        self.game_engine.input.set_dead_zones(stick, trigger);
+        self
+    }
+
     /**
     Adds a game object to the game engine.
     The game object must implement the `GameObject`
@@ -265,10 +525,16 @@ impl GameEngineBuilder {
       This will automatically be placed under the "assets/" directory.
     * `tile_width` - Width of each tile.
     * `tile_height` - Height of each tile.
+    * `columns` - The number of columns to load.
+    * `rows` - The number of rows to load.
 
     # Returns
 
-    `Self` - Returns the `GameEngineBuilder` instance for chaining.
+    `Result<Self, BujiError>` - Returns the `GameEngineBuilder` instance for chaining.
+
+    # Errors
+
+    Returns the error of `AssetServer::init` if the sprite sheet cannot be loaded.
     */
     pub fn add_asset_server(
         mut self,
@@ -277,16 +543,15 @@ impl GameEngineBuilder {
         tile_height: u32,
         columns: u32,
         rows: u32,
-    ) -> Self {
+    ) -> Result<Self, BujiError> {
         let base_path = "assets/";
         let full_path = Path::new(base_path).join(source_path);
-        let full_path_str = full_path.to_str().unwrap();
 
         self.game_engine
             .asset_server
-            .init(full_path_str, tile_width, tile_height, columns, rows);
+            .init(full_path, tile_width, tile_height, columns, rows)?;
 
-        self
+        Ok(self)
     }
 
     /**
@@ -294,10 +559,12 @@ impl GameEngineBuilder {
 
     # Returns
 
-    `Result<GameEngine<W>, String>` - Returns a `GameEngine` instance
-    if successful or an error message if the game object or FPS is not set.
+    `Result<GameEngine, BujiError>` - Returns a `GameEngine` instance
+    if successful or `BujiError::InvalidConfig` if the FPS, fixed update rate
+    or maximum number of fixed steps is zero.
     */
-    pub fn build(self) -> Result<GameEngine, String> {
+    pub fn build(self) -> Result<GameEngine, BujiError> {
+        self.game_engine.validate()?;
         Ok(self.game_engine)
     }
 }
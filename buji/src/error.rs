@@ -0,0 +1,94 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+/// Errors returned by the game engine.
+#[derive(Debug)]
+pub enum BujiError {
+    /// SDL2, its video subsystem or the event pump could not be initialized.
+    VideoInit(String),
+    /// The game window or its canvas could not be created.
+    WindowCreation(String),
+    /// A texture could not be drawn on the canvas.
+    Render(String),
+    /// An asset file could not be read.
+    AssetIo {
+        /// Path of the asset file.
+        path: PathBuf,
+        /// The underlying IO error.
+        source: std::io::Error,
+    },
+    /// An asset file could not be decoded or split into tiles.
+    ImageDecode {
+        /// Path of the asset file.
+        path: PathBuf,
+        /// The underlying image error.
+        source: image::ImageError,
+    },
+    /// A configuration file could not be read or written.
+    ConfigIo {
+        /// Path of the configuration file.
+        path: PathBuf,
+        /// The underlying IO error.
+        source: std::io::Error,
+    },
+    /// No figure exists with the given ID.
+    InvalidFigureId(u32),
+    /// An engine setting is invalid, e.g. a zero FPS or tile size.
+    InvalidConfig(String),
+}
+
+impl Display for BujiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BujiError::VideoInit(e) => write!(f, "Failed to initialize video : {}", e),
+            BujiError::WindowCreation(e) => write!(f, "Failed to create window : {}", e),
+            BujiError::Render(e) => write!(f, "Failed to render : {}", e),
+            BujiError::AssetIo { path, .. } => {
+                write!(f, "Failed to read asset '{}'", path.display())
+            }
+            BujiError::ImageDecode { path, .. } => {
+                write!(f, "Failed to decode image '{}'", path.display())
+            }
+            BujiError::ConfigIo { path, .. } => {
+                write!(f, "Failed to access config '{}'", path.display())
+            }
+            BujiError::InvalidFigureId(id) => write!(f, "Figure with ID {} does not exist", id),
+            BujiError::InvalidConfig(e) => write!(f, "Invalid configuration : {}", e),
+        }
+    }
+}
+
+impl Error for BujiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BujiError::AssetIo { source, .. } => Some(source),
+            BujiError::ImageDecode { source, .. } => Some(source),
+            BujiError::ConfigIo { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl BujiError {
+    /**
+    Creates an asset error from an image error, keeping IO failures apart from decoding failures.
+
+    # Arguments
+
+    * `path` - Path of the asset file.
+    * `error` - The error returned by the `image` crate.
+    */
+    pub(crate) fn from_image(path: impl Into<PathBuf>, error: image::ImageError) -> Self {
+        match error {
+            image::ImageError::IoError(source) => BujiError::AssetIo {
+                path: path.into(),
+                source,
+            },
+            source => BujiError::ImageDecode {
+                path: path.into(),
+                source,
+            },
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use crate::action::ActionMap;
+use crate::input::Input;
+use logy::ldebug;
+use sdl2::event::Event;
+use std::time::Duration;
+
+/**
+Per frame information passed to `GameObject::update` and `GameObject::fixed_update`.
+
+Use `delta` to make movement independent of the frame rate, e.g.
+`position += speed * context.delta_seconds()`.
+*/
+pub struct FrameContext<'a> {
+    /// Time spent on the previous frame, including the sleep that limits the FPS.
+    /// The fixed timestep in `fixed_update`.
+    pub delta: Duration,
+    /// Time elapsed since the engine entered the `Running` state.
+    /// The simulated time in `fixed_update`.
+    pub elapsed: Duration,
+    /// Number of the current frame, starting at zero.
+    /// The number of the fixed step in `fixed_update`.
+    pub frame: u64,
+    /// SDL2 events polled at the beginning of this frame.
+    events: &'a [Event],
+    /// Keyboard and mouse state of this frame.
+    input: &'a Input,
+    /// Named actions and axes of the engine.
+    actions: &'a mut ActionMap,
+}
+
+impl<'a> FrameContext<'a> {
+    /**
+    Creates a new `FrameContext`.
+
+    # Arguments
+
+    * `delta` - Time spent on the previous frame.
+    * `elapsed` - Time elapsed since the engine started running.
+    * `frame` - Number of the current frame.
+    * `events` - SDL2 events polled for this frame.
+    * `input` - Keyboard and mouse state of this frame.
+    * `actions` - Named actions and axes of the engine.
+    */
+    pub fn new(
+        delta: Duration,
+        elapsed: Duration,
+        frame: u64,
+        events: &'a [Event],
+        input: &'a Input,
+        actions: &'a mut ActionMap,
+    ) -> Self {
+        Self {
+            delta,
+            elapsed,
+            frame,
+            events,
+            input,
+            actions,
+        }
+    }
+
+    /**
+    Returns the delta time in seconds.
+
+    # Returns
+
+    `f32` - Time spent on the previous frame in seconds.
+    */
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /**
+    Returns the input events of this frame.
+
+    # Returns
+
+    `&[Event]` - SDL2 events polled at the beginning of this frame, in order.
+    */
+    pub fn events(&self) -> &'a [Event] {
+        self.events
+    }
+
+    /**
+    Returns the keyboard and mouse state.
+
+    # Returns
+
+    `&Input` - Keys and mouse buttons pressed, held or released, mouse position and wheel delta.
+    */
+    pub fn input(&self) -> &'a Input {
+        self.input
+    }
+
+    /**
+    Returns the action map, e.g. to list the current bindings in a settings menu.
+
+    # Returns
+
+    `&ActionMap` - Named actions and axes of the engine.
+    */
+    pub fn actions(&self) -> &ActionMap {
+        self.actions
+    }
+
+    /**
+    Returns the action map for rebinding controls at runtime.
+
+    # Returns
+
+    `&mut ActionMap` - Named actions and axes of the engine.
+    */
+    pub fn actions_mut(&mut self) -> &mut ActionMap {
+        self.actions
+    }
+
+    /**
+    Checks whether any binding of an action went down during this frame.
+
+    # Arguments
+
+    * `action` - Name of the action.
+    */
+    pub fn action_pressed(&self, action: &str) -> bool {
+        self.actions.pressed(self.input, action)
+    }
+
+    /**
+    Checks whether any binding of an action is currently down.
+
+    # Arguments
+
+    * `action` - Name of the action.
+    */
+    pub fn action_held(&self, action: &str) -> bool {
+        self.actions.held(self.input, action)
+    }
+
+    /**
+    Checks whether a binding of an action went up during this frame.
+
+    # Arguments
+
+    * `action` - Name of the action.
+    */
+    pub fn action_released(&self, action: &str) -> bool {
+        self.actions.released(self.input, action)
+    }
+
+    /**
+    Returns the value of an axis between -1 and 1.
+
+    # Arguments
+
+    * `axis` - Name of the axis.
+    */
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.actions.axis(self.input, axis)
+    }
+}
+
+/**
+Accumulates frame time for the fixed update mode and hands it out in whole steps.
+
+Time that would need more than `max_steps` steps in a single frame is dropped, so a slow
+`fixed_update` cannot make every following frame slower (the "spiral of death").
+*/
+pub(crate) struct FixedStep {
+    step: Duration,
+    max_steps: u32,
+    accumulator: Duration,
+}
+
+impl FixedStep {
+    /**
+    Creates a new `FixedStep`.
+
+    # Arguments
+
+    * `step` - The fixed timestep, must not be zero.
+    * `max_steps` - Maximum number of steps per frame.
+    */
+    pub(crate) fn new(step: Duration, max_steps: u32) -> Self {
+        Self {
+            step,
+            max_steps,
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /**
+    Returns the fixed timestep.
+    */
+    pub(crate) fn step(&self) -> Duration {
+        self.step
+    }
+
+    /**
+    Adds the time of a frame and takes the whole steps out of the accumulator.
+
+    # Arguments
+
+    * `delta` - Time spent on the previous frame.
+
+    # Returns
+
+    `u32` - The number of fixed updates to run this frame, at most `max_steps`.
+    */
+    pub(crate) fn accumulate(&mut self, delta: Duration) -> u32 {
+        self.accumulator += delta;
+        let max_accumulated = self.step * self.max_steps;
+        if self.accumulator > max_accumulated {
+            ldebug!(
+                "Simulation is {:?} behind, skipping",
+                self.accumulator - max_accumulated
+            );
+            self.accumulator = max_accumulated;
+        }
+
+        let steps = (self.accumulator.as_nanos() / self.step.as_nanos()) as u32;
+        self.accumulator -= self.step * steps;
+        steps
+    }
+
+    /**
+    Returns how far the simulation is between the last fixed step and the next one.
+
+    # Returns
+
+    `f32` - The interpolation alpha, in [0, 1).
+    */
+    pub(crate) fn alpha(&self) -> f32 {
+        let alpha = self.accumulator.as_secs_f64() / self.step.as_secs_f64();
+        // The accumulator is always less than a step, but rounding to `f32` may reach 1.
+        (alpha as f32).min(1.0 - f32::EPSILON / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn accumulate_runs_whole_steps_and_keeps_the_rest() {
+        let mut fixed = FixedStep::new(STEP, 5);
+        assert_eq!(fixed.accumulate(Duration::from_millis(4)), 0);
+        assert_eq!(fixed.accumulate(Duration::from_millis(17)), 2);
+        assert_eq!(fixed.accumulate(Duration::from_millis(9)), 1);
+        assert_eq!(fixed.accumulate(Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn accumulate_clamps_to_max_fixed_steps() {
+        let mut fixed = FixedStep::new(STEP, 5);
+        assert_eq!(fixed.accumulate(Duration::from_secs(3)), 5);
+        // The time beyond the clamp is dropped, not carried into the next frame.
+        assert_eq!(fixed.accumulate(Duration::ZERO), 0);
+        assert_eq!(fixed.alpha(), 0.0);
+        assert_eq!(fixed.accumulate(Duration::from_millis(10)), 1);
+    }
+
+    #[test]
+    fn alpha_stays_below_one() {
+        let mut fixed = FixedStep::new(Duration::new(0, 1_000_000_000 / 60), 5);
+        assert_eq!(fixed.alpha(), 0.0);
+        for nanos in [
+            1,
+            8_333_333,
+            16_666_665,
+            16_666_667,
+            49_999_999,
+            1_000_000_000,
+        ] {
+            fixed.accumulate(Duration::from_nanos(nanos));
+            let alpha = fixed.alpha();
+            assert!((0.0..1.0).contains(&alpha), "{} after {}ns", alpha, nanos);
+        }
+
+        let mut fixed = FixedStep::new(STEP, 5);
+        fixed.accumulate(STEP - Duration::from_nanos(1));
+        assert!(fixed.alpha() < 1.0);
+        fixed.accumulate(Duration::from_millis(5));
+        assert!((fixed.alpha() - 0.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,338 @@
+use crate::input::GamepadButton;
+use sdl2::controller::{Axis, GameController};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::time::Duration;
+
+pub use sdl2::controller::Axis as GamepadAxis;
+
+/**
+A connected game controller and its state in the current frame.
+
+Sticks and triggers are reported with their dead zones applied, so small
+movements of a worn stick are ignored.
+
+Clones share the underlying controller, so rumble works through any of them.
+*/
+#[derive(Clone)]
+pub struct Gamepad {
+    id: u32,
+    name: String,
+    /// `None` for gamepads created by tests, which have no device behind them.
+    controller: Option<Rc<RefCell<GameController>>>,
+    held: HashSet<GamepadButton>,
+    pressed: HashSet<GamepadButton>,
+    released: HashSet<GamepadButton>,
+    axes: [i16; 6],
+    stick_dead_zone: f32,
+    trigger_dead_zone: f32,
+}
+
+impl Gamepad {
+    pub(crate) fn new(
+        controller: GameController,
+        stick_dead_zone: f32,
+        trigger_dead_zone: f32,
+    ) -> Self {
+        let mut gamepad = Self::detached(
+            controller.instance_id(),
+            controller.name(),
+            stick_dead_zone,
+            trigger_dead_zone,
+        );
+        gamepad.controller = Some(Rc::new(RefCell::new(controller)));
+        gamepad
+    }
+
+    /// Creates a gamepad without a device, whose state only changes through events.
+    pub(crate) fn detached(
+        id: u32,
+        name: String,
+        stick_dead_zone: f32,
+        trigger_dead_zone: f32,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            controller: None,
+            held: HashSet::new(),
+            pressed: HashSet::new(),
+            released: HashSet::new(),
+            axes: [0; 6],
+            stick_dead_zone,
+            trigger_dead_zone,
+        }
+    }
+
+    /// Returns the instance ID of the controller, which stays the same until it is disconnected.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the name of the controller, e.g. "Xbox One Controller".
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /**
+    Checks whether a button went down during this frame.
+
+    # Arguments
+
+    * `button` - The button to check.
+    */
+    pub fn pressed(&self, button: GamepadButton) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    /**
+    Checks whether a button is currently down.
+
+    # Arguments
+
+    * `button` - The button to check.
+    */
+    pub fn held(&self, button: GamepadButton) -> bool {
+        self.held.contains(&button)
+    }
+
+    /**
+    Checks whether a button went up during this frame.
+
+    # Arguments
+
+    * `button` - The button to check.
+    */
+    pub fn released(&self, button: GamepadButton) -> bool {
+        self.released.contains(&button)
+    }
+
+    /**
+    Returns the position of the left stick.
+
+    # Returns
+
+    `(f32, f32)` - x and y between -1 and 1, positive y pointing down.
+    */
+    pub fn left_stick(&self) -> (f32, f32) {
+        self.stick(Axis::LeftX, Axis::LeftY)
+    }
+
+    /**
+    Returns the position of the right stick.
+
+    # Returns
+
+    `(f32, f32)` - x and y between -1 and 1, positive y pointing down.
+    */
+    pub fn right_stick(&self) -> (f32, f32) {
+        self.stick(Axis::RightX, Axis::RightY)
+    }
+
+    /**
+    Returns how far the left trigger is pulled.
+
+    # Returns
+
+    `f32` - A value between 0 and 1.
+    */
+    pub fn left_trigger(&self) -> f32 {
+        self.trigger(Axis::TriggerLeft)
+    }
+
+    /**
+    Returns how far the right trigger is pulled.
+
+    # Returns
+
+    `f32` - A value between 0 and 1.
+    */
+    pub fn right_trigger(&self) -> f32 {
+        self.trigger(Axis::TriggerRight)
+    }
+
+    /**
+    Returns the raw value of an axis, without dead zone.
+
+    # Arguments
+
+    * `axis` - The stick or trigger axis.
+
+    # Returns
+
+    `f32` - A value between -1 and 1 for sticks and between 0 and 1 for triggers.
+    */
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        normalize(self.axes[axis_index(axis)])
+    }
+
+    /// Checks whether the controller supports rumble.
+    pub fn has_rumble(&self) -> bool {
+        self.controller
+            .as_ref()
+            .is_some_and(|controller| controller.borrow().has_rumble())
+    }
+
+    /**
+    Starts a rumble effect, replacing the previous one.
+
+    # Arguments
+
+    * `low_frequency` - Strength of the low frequency (left) motor between 0 and 1.
+    * `high_frequency` - Strength of the high frequency (right) motor between 0 and 1.
+    * `duration` - How long the effect lasts.
+
+    # Returns
+
+    `bool` - `false` if the controller does not support rumble.
+    */
+    pub fn rumble(&self, low_frequency: f32, high_frequency: f32, duration: Duration) -> bool {
+        let strength = |value: f32| (value.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+        let Some(controller) = &self.controller else {
+            return false;
+        };
+        controller
+            .borrow_mut()
+            .set_rumble(
+                strength(low_frequency),
+                strength(high_frequency),
+                duration.as_millis().min(u32::MAX as u128) as u32,
+            )
+            .is_ok()
+    }
+
+    pub(crate) fn begin_frame(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+    }
+
+    pub(crate) fn button_down(&mut self, button: GamepadButton) {
+        if self.held.insert(button) {
+            self.pressed.insert(button);
+        }
+    }
+
+    pub(crate) fn button_up(&mut self, button: GamepadButton) {
+        if self.held.remove(&button) {
+            self.released.insert(button);
+        }
+    }
+
+    pub(crate) fn set_axis(&mut self, axis: GamepadAxis, value: i16) {
+        self.axes[axis_index(axis)] = value;
+    }
+
+    pub(crate) fn set_dead_zones(&mut self, stick: f32, trigger: f32) {
+        self.stick_dead_zone = stick;
+        self.trigger_dead_zone = trigger;
+    }
+
+    /// Applies a radial dead zone, so diagonal movements are not cut off.
+    fn stick(&self, x: Axis, y: Axis) -> (f32, f32) {
+        let (x, y) = (self.axis(x), self.axis(y));
+        let magnitude = (x * x + y * y).sqrt();
+        if magnitude <= self.stick_dead_zone {
+            return (0.0, 0.0);
+        }
+        let scaled = ((magnitude - self.stick_dead_zone) / (1.0 - self.stick_dead_zone)).min(1.0);
+        (x / magnitude * scaled, y / magnitude * scaled)
+    }
+
+    fn trigger(&self, axis: Axis) -> f32 {
+        let value = self.axis(axis);
+        if value <= self.trigger_dead_zone {
+            return 0.0;
+        }
+        ((value - self.trigger_dead_zone) / (1.0 - self.trigger_dead_zone)).min(1.0)
+    }
+}
+
+fn axis_index(axis: Axis) -> usize {
+    match axis {
+        Axis::LeftX => 0,
+        Axis::LeftY => 1,
+        Axis::RightX => 2,
+        Axis::RightY => 3,
+        Axis::TriggerLeft => 4,
+        Axis::TriggerRight => 5,
+    }
+}
+
+fn normalize(value: i16) -> f32 {
+    (value as f32 / i16::MAX as f32).max(-1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-3;
+
+    fn gamepad() -> Gamepad {
+        Gamepad::detached(1, "Test Pad".to_string(), 0.2, 0.1)
+    }
+
+    fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+        assert!(
+            (actual.0 - expected.0).abs() < EPSILON && (actual.1 - expected.1).abs() < EPSILON,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn stick_inside_dead_zone_is_centered() {
+        let mut gamepad = gamepad();
+        gamepad.set_axis(Axis::LeftX, (0.1 * i16::MAX as f32) as i16);
+        gamepad.set_axis(Axis::LeftY, (0.1 * i16::MAX as f32) as i16);
+        assert_eq!(gamepad.left_stick(), (0.0, 0.0));
+        assert!(gamepad.axis(Axis::LeftX) > 0.0);
+    }
+
+    #[test]
+    fn stick_is_scaled_radially_past_the_dead_zone() {
+        let mut gamepad = gamepad();
+        // Halfway between the dead zone and the edge, pointing right.
+        gamepad.set_axis(Axis::RightX, (0.6 * i16::MAX as f32) as i16);
+        assert_close(gamepad.right_stick(), (0.5, 0.0));
+
+        // A diagonal keeps its direction, only the magnitude is rescaled.
+        let component = 0.6 / 2f32.sqrt();
+        gamepad.set_axis(Axis::RightX, (component * i16::MAX as f32) as i16);
+        gamepad.set_axis(Axis::RightY, (component * i16::MAX as f32) as i16);
+        let half = 0.5 / 2f32.sqrt();
+        assert_close(gamepad.right_stick(), (half, half));
+    }
+
+    #[test]
+    fn stick_is_clamped_to_the_unit_circle() {
+        let mut gamepad = gamepad();
+        gamepad.set_axis(Axis::LeftX, i16::MAX);
+        gamepad.set_axis(Axis::LeftY, i16::MIN);
+        let (x, y) = gamepad.left_stick();
+        assert!((x * x + y * y).sqrt() <= 1.0 + EPSILON);
+        assert!(x > 0.0 && y < 0.0);
+    }
+
+    #[test]
+    fn trigger_applies_its_dead_zone() {
+        let mut gamepad = gamepad();
+        gamepad.set_axis(Axis::TriggerLeft, (0.05 * i16::MAX as f32) as i16);
+        assert_eq!(gamepad.left_trigger(), 0.0);
+
+        gamepad.set_axis(Axis::TriggerLeft, (0.55 * i16::MAX as f32) as i16);
+        assert!((gamepad.left_trigger() - 0.5).abs() < EPSILON);
+
+        gamepad.set_axis(Axis::TriggerRight, i16::MAX);
+        assert!((gamepad.right_trigger() - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn detached_gamepad_cannot_rumble() {
+        let gamepad = gamepad();
+        assert!(!gamepad.has_rumble());
+        assert!(!gamepad.rumble(1.0, 1.0, Duration::from_millis(100)));
+    }
+}
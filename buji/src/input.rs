@@ -0,0 +1,467 @@
+use crate::gamepad::Gamepad;
+use crate::{DEFAULT_STICK_DEAD_ZONE, DEFAULT_TRIGGER_DEAD_ZONE};
+use logy::*;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::mouse::MouseWheelDirection;
+use sdl2::GameControllerSubsystem;
+use std::collections::HashSet;
+
+pub use sdl2::controller::Button as GamepadButton;
+pub use sdl2::keyboard::Keycode;
+pub use sdl2::mouse::MouseButton;
+
+/**
+Keyboard and mouse state, updated by the engine at the beginning of every frame.
+
+"Pressed" and "released" are only true during the frame in which the change happened,
+"held" stays true as long as the key or button is down.
+
+# Example
+
+```rust
+use buji::{FrameContext, Keycode, MainState};
+
+fn update(context: &FrameContext) -> MainState {
+    let input = context.input();
+    if input.key_pressed(Keycode::Space) {
+        // Jump once per key press
+    }
+    if input.key_held(Keycode::Right) {
+        // Move right while the key is down
+    }
+    MainState::Running
+}
+```
+*/
+#[derive(Clone)]
+pub struct Input {
+    keys_held: HashSet<Keycode>,
+    keys_pressed: HashSet<Keycode>,
+    keys_released: HashSet<Keycode>,
+    buttons_held: HashSet<MouseButton>,
+    buttons_pressed: HashSet<MouseButton>,
+    buttons_released: HashSet<MouseButton>,
+    gamepads: Vec<Gamepad>,
+    stick_dead_zone: f32,
+    trigger_dead_zone: f32,
+    mouse_position: (i32, i32),
+    wheel_delta: (i32, i32),
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self {
+            keys_held: HashSet::new(),
+            keys_pressed: HashSet::new(),
+            keys_released: HashSet::new(),
+            buttons_held: HashSet::new(),
+            buttons_pressed: HashSet::new(),
+            buttons_released: HashSet::new(),
+            gamepads: Vec::new(),
+            stick_dead_zone: DEFAULT_STICK_DEAD_ZONE,
+            trigger_dead_zone: DEFAULT_TRIGGER_DEAD_ZONE,
+            mouse_position: (0, 0),
+            wheel_delta: (0, 0),
+        }
+    }
+}
+
+impl Input {
+    /**
+    Checks whether a key went down during this frame.
+
+    # Arguments
+
+    * `key` - The key to check.
+    */
+    pub fn key_pressed(&self, key: Keycode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    /**
+    Checks whether a key is currently down.
+
+    # Arguments
+
+    * `key` - The key to check.
+    */
+    pub fn key_held(&self, key: Keycode) -> bool {
+        self.keys_held.contains(&key)
+    }
+
+    /**
+    Checks whether a key went up during this frame.
+
+    # Arguments
+
+    * `key` - The key to check.
+    */
+    pub fn key_released(&self, key: Keycode) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    /**
+    Checks whether a mouse button went down during this frame.
+
+    # Arguments
+
+    * `button` - The mouse button to check.
+    */
+    pub fn mouse_pressed(&self, button: MouseButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    /**
+    Checks whether a mouse button is currently down.
+
+    # Arguments
+
+    * `button` - The mouse button to check.
+    */
+    pub fn mouse_held(&self, button: MouseButton) -> bool {
+        self.buttons_held.contains(&button)
+    }
+
+    /**
+    Checks whether a mouse button went up during this frame.
+
+    # Arguments
+
+    * `button` - The mouse button to check.
+    */
+    pub fn mouse_released(&self, button: MouseButton) -> bool {
+        self.buttons_released.contains(&button)
+    }
+
+    /**
+    Checks whether a gamepad button went down during this frame on any controller.
+
+    # Arguments
+
+    * `button` - The gamepad button to check.
+    */
+    pub fn gamepad_pressed(&self, button: GamepadButton) -> bool {
+        self.gamepads.iter().any(|gamepad| gamepad.pressed(button))
+    }
+
+    /**
+    Checks whether a gamepad button is currently down on any controller.
+
+    # Arguments
+
+    * `button` - The gamepad button to check.
+    */
+    pub fn gamepad_held(&self, button: GamepadButton) -> bool {
+        self.gamepads.iter().any(|gamepad| gamepad.held(button))
+    }
+
+    /**
+    Checks whether a gamepad button went up during this frame on any controller.
+
+    # Arguments
+
+    * `button` - The gamepad button to check.
+    */
+    pub fn gamepad_released(&self, button: GamepadButton) -> bool {
+        self.gamepads.iter().any(|gamepad| gamepad.released(button))
+    }
+
+    /**
+    Returns the connected game controllers.
+
+    # Returns
+
+    `&[Gamepad]` - The controllers in the order they were connected.
+    */
+    pub fn gamepads(&self) -> &[Gamepad] {
+        &self.gamepads
+    }
+
+    /**
+    Returns a connected game controller.
+
+    # Arguments
+
+    * `id` - The instance ID of the controller, see `Gamepad::id`.
+    */
+    pub fn gamepad(&self, id: u32) -> Option<&Gamepad> {
+        self.gamepads.iter().find(|gamepad| gamepad.id() == id)
+    }
+
+    /**
+    Sets the dead zones of every current and future game controller.
+
+    # Arguments
+
+    * `stick` - Stick movements smaller than this (0..1) are reported as zero.
+    * `trigger` - Trigger values smaller than this (0..1) are reported as zero.
+    */
+    pub fn set_dead_zones(&mut self, stick: f32, trigger: f32) {
+        self.stick_dead_zone = stick.clamp(0.0, 0.99);
+        self.trigger_dead_zone = trigger.clamp(0.0, 0.99);
+        for gamepad in &mut self.gamepads {
+            gamepad.set_dead_zones(self.stick_dead_zone, self.trigger_dead_zone);
+        }
+    }
+
+    /**
+    Returns the last known mouse position.
+
+    # Returns
+
+    `(i32, i32)` - x and y in window coordinates.
+    */
+    pub fn mouse_position(&self) -> (i32, i32) {
+        self.mouse_position
+    }
+
+    /**
+    Returns how far the mouse wheel scrolled during this frame.
+
+    # Returns
+
+    `(i32, i32)` - Horizontal and vertical scroll amount. Positive y scrolls away from the user.
+    */
+    pub fn wheel_delta(&self) -> (i32, i32) {
+        self.wheel_delta
+    }
+
+    /// Forgets the per frame states. Called before the events of a new frame are handled.
+    pub(crate) fn begin_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.buttons_pressed.clear();
+        self.buttons_released.clear();
+        for gamepad in &mut self.gamepads {
+            gamepad.begin_frame();
+        }
+        self.wheel_delta = (0, 0);
+    }
+
+    /// Opens a game controller that has just been plugged in.
+    ///
+    /// Returns the new gamepad, or `None` if it could not be opened or was already connected.
+    pub(crate) fn connect_gamepad(
+        &mut self,
+        subsystem: &GameControllerSubsystem,
+        index: u32,
+    ) -> Option<&Gamepad> {
+        match subsystem.open(index) {
+            Ok(controller) => {
+                let gamepad =
+                    Gamepad::new(controller, self.stick_dead_zone, self.trigger_dead_zone);
+                linfo!("Gamepad connected: {}", gamepad.name());
+                self.add_gamepad(gamepad)
+            }
+            Err(e) => {
+                lwarn!("Failed to open gamepad {} : {}", index, e);
+                None
+            }
+        }
+    }
+
+    /// Adds a gamepad unless one with the same ID is already connected.
+    pub(crate) fn add_gamepad(&mut self, gamepad: Gamepad) -> Option<&Gamepad> {
+        if self.gamepad(gamepad.id()).is_some() {
+            return None;
+        }
+        self.gamepads.push(gamepad);
+        self.gamepads.last()
+    }
+
+    fn gamepad_mut(&mut self, id: u32) -> Option<&mut Gamepad> {
+        self.gamepads.iter_mut().find(|gamepad| gamepad.id() == id)
+    }
+
+    /// Updates the state from a single SDL2 event.
+    pub(crate) fn handle_event(&mut self, event: &Event) {
+        match *event {
+            Event::KeyDown {
+                keycode: Some(key),
+                repeat: false,
+                ..
+            } if self.keys_held.insert(key) => {
+                self.keys_pressed.insert(key);
+            }
+            Event::KeyUp {
+                keycode: Some(key), ..
+            } if self.keys_held.remove(&key) => {
+                self.keys_released.insert(key);
+            }
+            Event::MouseButtonDown {
+                mouse_btn, x, y, ..
+            } => {
+                self.mouse_position = (x, y);
+                if self.buttons_held.insert(mouse_btn) {
+                    self.buttons_pressed.insert(mouse_btn);
+                }
+            }
+            Event::MouseButtonUp {
+                mouse_btn, x, y, ..
+            } => {
+                self.mouse_position = (x, y);
+                if self.buttons_held.remove(&mouse_btn) {
+                    self.buttons_released.insert(mouse_btn);
+                }
+            }
+            Event::ControllerButtonDown { which, button, .. } => {
+                if let Some(gamepad) = self.gamepad_mut(which) {
+                    gamepad.button_down(button);
+                }
+            }
+            Event::ControllerButtonUp { which, button, .. } => {
+                if let Some(gamepad) = self.gamepad_mut(which) {
+                    gamepad.button_up(button);
+                }
+            }
+            Event::ControllerAxisMotion {
+                which, axis, value, ..
+            } => {
+                if let Some(gamepad) = self.gamepad_mut(which) {
+                    gamepad.set_axis(axis, value);
+                }
+            }
+            Event::ControllerDeviceRemoved { which, .. } => {
+                if let Some(index) = self.gamepads.iter().position(|g| g.id() == which) {
+                    let gamepad = self.gamepads.remove(index);
+                    linfo!("Gamepad disconnected: {}", gamepad.name());
+                }
+            }
+            Event::MouseMotion { x, y, .. } => self.mouse_position = (x, y),
+            Event::MouseWheel {
+                x, y, direction, ..
+            } => {
+                let sign = match direction {
+                    MouseWheelDirection::Flipped => -1,
+                    _ => 1,
+                };
+                self.wheel_delta.0 += x * sign;
+                self.wheel_delta.1 += y * sign;
+            }
+            Event::Window {
+                win_event: WindowEvent::FocusLost,
+                ..
+            } => self.release_all(),
+            _ => {}
+        }
+    }
+
+    /// Releases every held key and button, since their up events are not received without focus.
+    fn release_all(&mut self) {
+        self.keys_released.extend(self.keys_held.drain());
+        self.buttons_released.extend(self.buttons_held.drain());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GamepadAxis;
+
+    const PAD: u32 = 7;
+
+    fn input_with_gamepad() -> Input {
+        let mut input = Input::default();
+        input.set_dead_zones(0.2, 0.1);
+        let gamepad = Gamepad::detached(
+            PAD,
+            "Test Pad".to_string(),
+            input.stick_dead_zone,
+            input.trigger_dead_zone,
+        );
+        assert!(input.add_gamepad(gamepad).is_some());
+        input
+    }
+
+    fn button(down: bool, which: u32, button: GamepadButton) -> Event {
+        if down {
+            Event::ControllerButtonDown {
+                timestamp: 0,
+                which,
+                button,
+            }
+        } else {
+            Event::ControllerButtonUp {
+                timestamp: 0,
+                which,
+                button,
+            }
+        }
+    }
+
+    fn axis(axis: GamepadAxis, value: f32) -> Event {
+        Event::ControllerAxisMotion {
+            timestamp: 0,
+            which: PAD,
+            axis,
+            value: (value * i16::MAX as f32) as i16,
+        }
+    }
+
+    #[test]
+    fn button_events_update_pressed_held_and_released() {
+        let mut input = input_with_gamepad();
+
+        input.handle_event(&button(true, PAD, GamepadButton::A));
+        assert!(input.gamepad_pressed(GamepadButton::A));
+        assert!(input.gamepad_held(GamepadButton::A));
+
+        input.begin_frame();
+        input.handle_event(&button(true, PAD, GamepadButton::A));
+        assert!(!input.gamepad_pressed(GamepadButton::A));
+        assert!(input.gamepad_held(GamepadButton::A));
+
+        input.begin_frame();
+        input.handle_event(&button(false, PAD, GamepadButton::A));
+        assert!(input.gamepad_released(GamepadButton::A));
+        assert!(!input.gamepad_held(GamepadButton::A));
+    }
+
+    #[test]
+    fn events_of_unknown_gamepads_are_ignored() {
+        let mut input = input_with_gamepad();
+        input.handle_event(&button(true, PAD + 1, GamepadButton::B));
+        assert!(!input.gamepad_held(GamepadButton::B));
+    }
+
+    #[test]
+    fn axis_motion_applies_dead_zones() {
+        let mut input = input_with_gamepad();
+
+        input.handle_event(&axis(GamepadAxis::LeftX, 0.15));
+        input.handle_event(&axis(GamepadAxis::TriggerRight, 0.05));
+        let gamepad = input.gamepad(PAD).unwrap();
+        assert_eq!(gamepad.left_stick(), (0.0, 0.0));
+        assert_eq!(gamepad.right_trigger(), 0.0);
+
+        input.handle_event(&axis(GamepadAxis::LeftX, 0.6));
+        input.handle_event(&axis(GamepadAxis::TriggerRight, 0.55));
+        let gamepad = input.gamepad(PAD).unwrap();
+        assert!((gamepad.left_stick().0 - 0.5).abs() < 1e-3);
+        assert!((gamepad.right_trigger() - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn set_dead_zones_reaches_connected_gamepads() {
+        let mut input = input_with_gamepad();
+        input.handle_event(&axis(GamepadAxis::LeftY, 0.3));
+        assert!(input.gamepad(PAD).unwrap().left_stick().1 > 0.0);
+
+        input.set_dead_zones(0.4, 0.1);
+        assert_eq!(input.gamepad(PAD).unwrap().left_stick(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn device_removed_disconnects_the_gamepad() {
+        let mut input = input_with_gamepad();
+        input.handle_event(&button(true, PAD, GamepadButton::Start));
+
+        input.handle_event(&Event::ControllerDeviceRemoved {
+            timestamp: 0,
+            which: PAD,
+        });
+        assert!(input.gamepad(PAD).is_none());
+        assert!(input.gamepads().is_empty());
+        assert!(!input.gamepad_held(GamepadButton::Start));
+    }
+}
@@ -1,10 +1,25 @@
+mod action;
 mod asset_server;
 mod constants;
 mod core;
+mod error;
+mod frame;
+mod gamepad;
+mod input;
+mod render;
+mod texture;
 mod ui;
 mod world;
 
+pub use action::*;
 pub use asset_server::*;
 pub use constants::*;
 pub use core::*;
+pub use error::*;
+pub use frame::*;
+pub use gamepad::*;
+pub use input::*;
+pub use render::*;
+pub use texture::*;
 pub use ui::*;
+pub use world::*;
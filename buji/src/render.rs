@@ -0,0 +1,376 @@
+use crate::texture::TextureManager;
+use crate::{AssetServer, BujiError, GameColor, Position, Scale2D};
+use sdl2::pixels::Color;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{BlendMode, Canvas, RenderTarget};
+use sdl2::video::Window;
+
+/**
+Scale, rotation and flip of a tile drawn with `RenderContext::draw_tile`.
+
+# Example
+
+```rust
+use buji::TileParams;
+
+let params = TileParams::default().with_scale(2.0).with_rotation(90.0).flipped(true, false);
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileParams {
+    /// Size multiplier of the tile, 1 draws it in its original size.
+    pub scale: f32,
+    /// Clockwise rotation around the center of the tile, in degrees.
+    pub rotation: f64,
+    /// Mirrors the tile horizontally.
+    pub flip_horizontal: bool,
+    /// Mirrors the tile vertically.
+    pub flip_vertical: bool,
+}
+
+impl Default for TileParams {
+    /// Returns the parameters that draw a tile unchanged.
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            rotation: 0.0,
+            flip_horizontal: false,
+            flip_vertical: false,
+        }
+    }
+}
+
+impl TileParams {
+    /**
+    Sets the size multiplier.
+
+    # Arguments
+
+    * `scale` - 1 draws the tile in its original size.
+    */
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /**
+    Sets the rotation.
+
+    # Arguments
+
+    * `degrees` - Clockwise rotation around the center of the tile.
+    */
+    pub fn with_rotation(mut self, degrees: f64) -> Self {
+        self.rotation = degrees;
+        self
+    }
+
+    /**
+    Sets the mirroring.
+
+    # Arguments
+
+    * `horizontal` - Mirrors the tile horizontally.
+    * `vertical` - Mirrors the tile vertically.
+    */
+    pub fn flipped(mut self, horizontal: bool, vertical: bool) -> Self {
+        self.flip_horizontal = horizontal;
+        self.flip_vertical = vertical;
+        self
+    }
+}
+
+/**
+Drawing operations passed to `GameObject::draw`.
+
+Everything is drawn on the `GameWindow` canvas and clipped to it. Colors are blended
+with what is already drawn according to their alpha.
+
+# Type Parameters
+
+* `T` - The render target, the game window unless drawing off screen.
+*/
+pub struct RenderContext<'a, T: RenderTarget = Window> {
+    canvas: &'a mut Canvas<T>,
+    asset_server: &'a AssetServer,
+    textures: &'a TextureManager<'a>,
+    alpha: f32,
+}
+
+impl<'a, T: RenderTarget> RenderContext<'a, T> {
+    /**
+    Creates a new `RenderContext`, clips the canvas to the window and enables alpha blending.
+
+    # Arguments
+
+    * `canvas` - The canvas of the game window.
+    * `window_size` - Size of the game window.
+    * `asset_server` - The asset server holding the sprite sheet tiles.
+    * `textures` - The textures uploaded for the canvas.
+    * `alpha` - Interpolation between the last two fixed updates.
+    */
+    pub fn new(
+        canvas: &'a mut Canvas<T>,
+        window_size: &Scale2D,
+        asset_server: &'a AssetServer,
+        textures: &'a TextureManager<'a>,
+        alpha: f32,
+    ) -> Self {
+        canvas.set_clip_rect(Rect::new(0, 0, window_size.width, window_size.height));
+        canvas.set_blend_mode(BlendMode::Blend);
+        Self {
+            canvas,
+            asset_server,
+            textures,
+            alpha,
+        }
+    }
+
+    /// Returns the asset server.
+    pub fn asset_server(&self) -> &'a AssetServer {
+        self.asset_server
+    }
+
+    /**
+    Returns how far the simulation is between the last two fixed updates.
+
+    # Returns
+
+    `f32` - A value between 0 and 1 to interpolate positions with when the fixed
+    update mode is enabled. Always `1.0` otherwise.
+    */
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    /**
+    Draws a sprite sheet tile.
+
+    # Arguments
+
+    * `index` - Index of the tile in the `AssetServer`.
+    * `position` - Top left corner of the tile before rotation.
+    * `params` - Scale, rotation and flip of the tile.
+
+    # Errors
+
+    Returns `BujiError::Render` if the tile does not exist or cannot be drawn.
+    */
+    pub fn draw_tile(
+        &mut self,
+        index: usize,
+        position: Position,
+        params: &TileParams,
+    ) -> Result<(), BujiError> {
+        let source = self
+            .asset_server
+            .tile_rect(index)
+            .ok_or_else(|| BujiError::Render(format!("tile {} does not exist", index)))?;
+        let texture = self
+            .textures
+            .sprite_sheet()
+            .ok_or_else(|| BujiError::Render("no sprite sheet is loaded".to_string()))?;
+
+        let target = Rect::new(
+            position.x,
+            position.y,
+            (source.width() as f32 * params.scale).round() as u32,
+            (source.height() as f32 * params.scale).round() as u32,
+        );
+        self.canvas
+            .copy_ex(
+                texture,
+                source,
+                target,
+                params.rotation,
+                None,
+                params.flip_horizontal,
+                params.flip_vertical,
+            )
+            .map_err(BujiError::Render)
+    }
+
+    /**
+    Draws a filled rectangle.
+
+    # Arguments
+
+    * `position` - Top left corner of the rectangle.
+    * `size` - Width and height of the rectangle.
+    * `color` - Fill color.
+
+    # Errors
+
+    Returns `BujiError::Render` if the rectangle cannot be drawn.
+    */
+    pub fn fill_rect(
+        &mut self,
+        position: Position,
+        size: Scale2D,
+        color: &GameColor,
+    ) -> Result<(), BujiError> {
+        self.canvas.set_draw_color(Color::from(color));
+        self.canvas
+            .fill_rect(Rect::new(position.x, position.y, size.width, size.height))
+            .map_err(BujiError::Render)
+    }
+
+    /**
+    Draws the outline of a rectangle.
+
+    # Arguments
+
+    * `position` - Top left corner of the rectangle.
+    * `size` - Width and height of the rectangle.
+    * `color` - Outline color.
+
+    # Errors
+
+    Returns `BujiError::Render` if the rectangle cannot be drawn.
+    */
+    pub fn draw_rect(
+        &mut self,
+        position: Position,
+        size: Scale2D,
+        color: &GameColor,
+    ) -> Result<(), BujiError> {
+        self.canvas.set_draw_color(Color::from(color));
+        self.canvas
+            .draw_rect(Rect::new(position.x, position.y, size.width, size.height))
+            .map_err(BujiError::Render)
+    }
+
+    /**
+    Draws a line.
+
+    # Arguments
+
+    * `from` - Start of the line.
+    * `to` - End of the line.
+    * `color` - Line color.
+
+    # Errors
+
+    Returns `BujiError::Render` if the line cannot be drawn.
+    */
+    pub fn draw_line(
+        &mut self,
+        from: Position,
+        to: Position,
+        color: &GameColor,
+    ) -> Result<(), BujiError> {
+        self.canvas.set_draw_color(Color::from(color));
+        self.canvas
+            .draw_line(Point::new(from.x, from.y), Point::new(to.x, to.y))
+            .map_err(BujiError::Render)
+    }
+
+    /**
+    Draws a single pixel.
+
+    # Arguments
+
+    * `point` - Position of the pixel.
+    * `color` - Pixel color.
+
+    # Errors
+
+    Returns `BujiError::Render` if the point cannot be drawn.
+    */
+    pub fn draw_point(&mut self, point: Position, color: &GameColor) -> Result<(), BujiError> {
+        self.canvas.set_draw_color(Color::from(color));
+        self.canvas
+            .draw_point(Point::new(point.x, point.y))
+            .map_err(BujiError::Render)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdl2::pixels::PixelFormatEnum;
+    use sdl2::surface::Surface;
+
+    const SIZE: u32 = 8;
+
+    /// Draws on a software canvas, which needs no window, and returns its RGBA pixels.
+    fn render(draw: impl FnOnce(&mut RenderContext<Surface<'static>>)) -> Vec<u8> {
+        let mut canvas: Canvas<Surface<'static>> =
+            Surface::new(SIZE, SIZE, PixelFormatEnum::RGBA32)
+                .unwrap()
+                .into_canvas()
+                .unwrap();
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, 255));
+        canvas.clear();
+        let texture_creator = canvas.texture_creator();
+        let asset_server = AssetServer::default();
+        let textures = TextureManager::new(&texture_creator, &asset_server).unwrap();
+        let mut context = RenderContext::new(
+            &mut canvas,
+            &Scale2D::new(SIZE, SIZE),
+            &asset_server,
+            &textures,
+            1.0,
+        );
+        draw(&mut context);
+        canvas.read_pixels(None, PixelFormatEnum::RGBA32).unwrap()
+    }
+
+    fn pixel(pixels: &[u8], x: u32, y: u32) -> [u8; 4] {
+        let start = ((y * SIZE + x) * 4) as usize;
+        pixels[start..start + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn fill_rect_uses_the_given_channels() {
+        let pixels = render(|context| {
+            context
+                .fill_rect(
+                    Position::new(1, 1),
+                    Scale2D::new(2, 2),
+                    &GameColor::new(0, 255, 0, 100),
+                )
+                .unwrap();
+        });
+        assert_eq!(pixel(&pixels, 1, 1), [0, 255, 0, 255]);
+        assert_eq!(pixel(&pixels, 2, 2), [0, 255, 0, 255]);
+        assert_eq!(pixel(&pixels, 3, 3), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn alpha_blends_with_the_background() {
+        let pixels = render(|context| {
+            context
+                .draw_point(Position::new(0, 0), &GameColor::new(255, 0, 0, 50))
+                .unwrap();
+        });
+        let [red, green, blue, _] = pixel(&pixels, 0, 0);
+        assert!((120..=136).contains(&red), "{}", red);
+        assert_eq!((green, blue), (0, 0));
+    }
+
+    #[test]
+    fn draw_rect_and_draw_line_draw_outlines() {
+        let pixels = render(|context| {
+            let white = GameColor::from_rgb([255, 255, 255]);
+            context
+                .draw_rect(Position::new(0, 0), Scale2D::new(4, 4), &white)
+                .unwrap();
+            context
+                .draw_line(Position::new(0, 6), Position::new(7, 6), &white)
+                .unwrap();
+        });
+        assert_eq!(pixel(&pixels, 0, 0), [255, 255, 255, 255]);
+        assert_eq!(pixel(&pixels, 3, 3), [255, 255, 255, 255]);
+        assert_eq!(pixel(&pixels, 1, 1), [0, 0, 0, 255]);
+        assert_eq!(pixel(&pixels, 5, 6), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn draw_tile_fails_without_tiles() {
+        render(|context| {
+            let result = context.draw_tile(0, Position::new(0, 0), &TileParams::default());
+            assert!(matches!(result, Err(BujiError::Render(_))));
+        });
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{AssetServer, BujiError};
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::{BlendMode, Texture, TextureCreator};
+
+/**
+Owns the GPU textures of the assets, so drawing a tile is a single copy on the canvas.
+
+Textures belong to the canvas they were created for, so the engine creates the
+texture manager in `GameEngine::run` once the window is ready.
+
+# Type Parameters
+
+* `'r` - Lifetime of the `TextureCreator` the textures were created with.
+*/
+pub struct TextureManager<'r> {
+    /// The sprite sheet of the `AssetServer`, if one has been loaded.
+    sprite_sheet: Option<Texture<'r>>,
+}
+
+impl<'r> TextureManager<'r> {
+    /**
+    Uploads the sprite sheet of the asset server as a texture.
+
+    # Arguments
+
+    * `texture_creator` - Texture creator of the canvas the textures are drawn on.
+    * `asset_server` - The asset server holding the decoded sprite sheet.
+
+    # Errors
+
+    Returns `BujiError::Render` if the texture cannot be created or filled.
+    */
+    pub fn new<C>(
+        texture_creator: &'r TextureCreator<C>,
+        asset_server: &AssetServer,
+    ) -> Result<Self, BujiError> {
+        let sprite_sheet = match asset_server.sprite_sheet() {
+            Some(image) => {
+                let (width, height) = image.dimensions();
+                let mut texture = texture_creator
+                    .create_texture_static(PixelFormatEnum::RGBA32, width, height)
+                    .map_err(|e| BujiError::Render(e.to_string()))?;
+                texture
+                    .update(None, image.as_raw(), width as usize * 4)
+                    .map_err(|e| BujiError::Render(e.to_string()))?;
+                texture.set_blend_mode(BlendMode::Blend);
+                Some(texture)
+            }
+            None => None,
+        };
+
+        Ok(Self { sprite_sheet })
+    }
+
+    /// Returns the sprite sheet texture, if the asset server had a sprite sheet.
+    pub fn sprite_sheet(&self) -> Option<&Texture<'r>> {
+        self.sprite_sheet.as_ref()
+    }
+}
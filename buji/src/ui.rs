@@ -1,10 +1,12 @@
+use crate::BujiError;
 use logy::*;
 use sdl2::pixels::Color;
 use sdl2::render::Canvas;
 use sdl2::video::Window;
-use sdl2::Sdl;
+use sdl2::{GameControllerSubsystem, Sdl};
 
 /// Fundamental Game Color data structure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct GameColor {
     /// Red color value between 0..255
     pub red: u8,
@@ -50,21 +52,21 @@ impl GameColor {
     pub fn from_rgb(codes: [u8; 3]) -> Self {
         Self {
             red: codes[0],
-            blue: codes[1],
-            green: codes[2],
-            alpha: 0,
+            green: codes[1],
+            blue: codes[2],
+            alpha: 100,
         }
     }
 }
 
 impl Default for GameColor {
-    /// Initialize a default Color
+    /// Initialize a default Color, opaque black
     fn default() -> Self {
         Self {
             red: 0,
             green: 0,
             blue: 0,
-            alpha: 0,
+            alpha: 100,
         }
     }
 }
@@ -73,16 +75,20 @@ impl From<&GameColor> for Color {
     /**
     Convert GameColor instance to SDL2 Color instance
 
+    The alpha percentage (0..100) is scaled to SDL's 0..255 range.
+
     # Parameters
 
     - `color`: Instance of GameColor
     */
     fn from(color: &GameColor) -> Self {
-        Self::RGBA(color.red, color.blue, color.green, color.alpha)
+        let alpha = (color.alpha.min(100) as u16 * 255 + 50) / 100;
+        Self::RGBA(color.red, color.green, color.blue, alpha as u8)
     }
 }
 
 /// Represents a 2D scale with width and height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Scale2D {
     /// The width of the scale.
     pub width: u32,
@@ -134,6 +140,8 @@ pub struct GameWindow {
     pub background_color: GameColor,
     /// SDL2 Context
     pub sdl_context: Option<Sdl>,
+    /// SDL2 game controller subsystem, `None` if it could not be initialized
+    pub game_controller: Option<GameControllerSubsystem>,
     /// Canvas zone
     pub canvas: Option<Canvas<Window>>,
 }
@@ -163,35 +171,51 @@ impl GameWindow {
 
     /**
     Initializes the SDL2 context and creates the game window and canvas.
+    Also initializes the game controller subsystem. If that fails, gamepads are disabled
+    with a warning instead of returning an error.
 
     # Returns
 
-    `Result<(), String>` - Returns `Ok(())`
-    if the initialization is successful or an error message if something goes wrong.
+    `Result<(), BujiError>` - Returns `Ok(())`
+    if the initialization is successful or an error if something goes wrong.
 
     # Errors
 
-    This function will return an error if SDL2 fails to initialize or if the window or canvas creation fails.
+    Returns `BujiError::VideoInit` if SDL2 or its video subsystem fails to initialize
+    and `BujiError::WindowCreation` if the window or canvas creation fails.
     */
-    pub fn init(&mut self) -> Result<(), String> {
-        let sdl_context = sdl2::init()?;
-        let video_subsystem = sdl_context.video()?;
+    pub fn init(&mut self) -> Result<(), BujiError> {
+        let sdl_context = sdl2::init().map_err(BujiError::VideoInit)?;
+        let video_subsystem = sdl_context.video().map_err(BujiError::VideoInit)?;
+        let game_controller = sdl_context
+            .game_controller()
+            .map_err(|e| {
+                lwarn!(
+                    "Gamepads are disabled. Failed to initialize game controllers : {}",
+                    e
+                )
+            })
+            .ok();
 
         let window = video_subsystem
             .window(self.title, self.scale2d.width, self.scale2d.height)
             .position_centered()
             .build()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| BujiError::WindowCreation(e.to_string()))?;
 
-        let mut canvas = window.into_canvas().build().map_err(|e| e.to_string())?;
+        let mut canvas = window
+            .into_canvas()
+            .build()
+            .map_err(|e| BujiError::WindowCreation(e.to_string()))?;
         canvas.set_draw_color(Color::from(&self.background_color));
         canvas.clear();
         canvas.present();
 
         self.sdl_context = Some(sdl_context);
+        self.game_controller = game_controller;
         self.canvas = Some(canvas);
 
-        linfo!(LogLevel::Warn, "Video sub system is ready");
+        lwarn!("Video sub system is ready");
 
         Ok(())
     }
@@ -236,6 +260,7 @@ impl Default for GameWindow {
             title: "Anonymous Game",
             scale2d: Scale2D::default(),
             sdl_context: None,
+            game_controller: None,
             canvas: None,
             background_color: GameColor::default(),
         }
@@ -243,7 +268,7 @@ impl Default for GameWindow {
 }
 
 /// 2D Positions of something
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Position {
     /// x origin value
     pub x: i32,
@@ -268,3 +293,29 @@ impl Position {
         Self { x, y }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BLUE, GREEN};
+
+    #[test]
+    fn color_conversion_keeps_channels_and_scales_alpha() {
+        assert_eq!(
+            Color::from(&GameColor::new(10, 20, 30, 100)),
+            Color::RGBA(10, 20, 30, 255)
+        );
+        assert_eq!(Color::from(&GameColor::new(0, 0, 0, 50)).a, 128);
+        assert_eq!(Color::from(&GameColor::new(0, 0, 0, 0)).a, 0);
+        assert_eq!(Color::from(&GameColor::new(0, 0, 0, 200)).a, 255);
+    }
+
+    #[test]
+    fn from_rgb_is_opaque_and_keeps_channel_order() {
+        assert_eq!(GameColor::from_rgb(GREEN), GameColor::new(0, 255, 0, 100));
+        assert_eq!(
+            Color::from(&GameColor::from_rgb(BLUE)),
+            Color::RGBA(0, 0, 255, 255)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Position, Scale2D};
+use crate::{BujiError, Position, Scale2D};
 use logy::*;
 use sdl2::rect::Rect;
 use sdl2::render::{Canvas, Texture};
@@ -50,16 +50,24 @@ impl<'a> World<'a> {
     * `figure_id` - The ID of the figure to which the texture will be assigned.
     * `texture` - The SDL2 texture to be loaded for the figure.
 
-    # Panics
+    # Errors
 
-    Panics if the figure with the given ID does not exist.
+    Returns `BujiError::InvalidFigureId` if the figure with the given ID does not exist.
     */
-    pub fn load_texture(&mut self, figure_id: u32, texture: Texture<'a>) {
-        if let Some(figure) = self.figures.get_mut(figure_id as usize) {
-            figure.texture = Some(texture);
-        } else {
-            linfo!(LogLevel::Warn, "Tried to load an invalid texture");
-            panic!("Figure with ID {} does not exist", figure_id);
+    pub fn load_texture(&mut self, figure_id: u32, texture: Texture<'a>) -> Result<(), BujiError> {
+        match self
+            .figures
+            .iter_mut()
+            .find(|figure| figure.id == figure_id)
+        {
+            Some(figure) => {
+                figure.texture = Some(texture);
+                Ok(())
+            }
+            None => {
+                lwarn!("Tried to load an invalid texture");
+                Err(BujiError::InvalidFigureId(figure_id))
+            }
         }
     }
 }
@@ -91,14 +99,45 @@ impl<'a> Figure<'a> {
 
     * `canvas` - A mutable reference to the SDL2 canvas on which the figure will be drawn.
 
-    # Panics
+    # Errors
 
-    Panics if the texture cannot be rendered due to SDL2 errors.
+    Returns `BujiError::Render` if the texture cannot be rendered due to SDL2 errors.
     */
-    pub fn draw(&self, canvas: &mut Canvas<Window>) {
+    pub fn draw(&self, canvas: &mut Canvas<Window>) -> Result<(), BujiError> {
         if let Some(ref texture) = self.texture {
             let target_rect = Rect::new(self.pos.x, self.pos.y, self.size.width, self.size.height);
-            canvas.copy(texture, None, target_rect).unwrap();
+            canvas
+                .copy(texture, None, target_rect)
+                .map_err(BujiError::Render)?;
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdl2::pixels::PixelFormatEnum;
+    use sdl2::surface::Surface;
+
+    #[test]
+    fn load_texture_warns_about_unknown_figure() {
+        // A software canvas needs no window, so the test runs without a display.
+        let canvas = Surface::new(1, 1, PixelFormatEnum::RGBA32)
+            .unwrap()
+            .into_canvas()
+            .unwrap();
+        let texture_creator = canvas.texture_creator();
+        let texture = texture_creator
+            .create_texture_static(PixelFormatEnum::RGBA32, 1, 1)
+            .unwrap();
+        let mut world = World::default();
+        world.create_figure(Position::new(0, 0), Scale2D::new(1, 1));
+        let _capture = LogCapture::start();
+
+        let result = world.load_texture(42, texture);
+
+        assert!(matches!(result, Err(BujiError::InvalidFigureId(42))));
+        assert_logged!(LogLevel::Warn, contains "invalid texture");
     }
 }
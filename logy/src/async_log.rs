@@ -0,0 +1,245 @@
+use crate::log::{dispatch, SharedSinks};
+use crate::log_level::LogLevel;
+use crate::record::Record;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::JoinHandle;
+
+thread_local! {
+    /// Set on the background thread, which must never wait for itself.
+    static ON_WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Checks whether the current thread is the background thread of an asynchronous `Log`.
+pub(crate) fn on_worker_thread() -> bool {
+    ON_WORKER.get()
+}
+
+/// What to do with a new record when the asynchronous queue is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait until the background thread makes room. No record is lost.
+    #[default]
+    Block,
+    /// Discard the new record.
+    DropNewest,
+    /// Discard the oldest queued record to make room for the new one.
+    DropOldest,
+}
+
+/// Configuration of the asynchronous logging mode.
+#[derive(Clone, Copy, Debug)]
+pub struct AsyncConfig {
+    /// Maximum number of queued records.
+    pub capacity: usize,
+    /// What to do when the queue is full.
+    pub overflow: OverflowPolicy,
+}
+
+impl AsyncConfig {
+    /// Creates a new configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum number of queued records (at least 1).
+    /// * `overflow` - What to do when the queue is full.
+    pub fn new(capacity: usize, overflow: OverflowPolicy) -> AsyncConfig {
+        Self {
+            capacity: capacity.max(1),
+            overflow,
+        }
+    }
+}
+
+impl Default for AsyncConfig {
+    /// A queue of 1024 records that blocks when full.
+    fn default() -> Self {
+        Self::new(1024, OverflowPolicy::Block)
+    }
+}
+
+struct QueueState {
+    records: VecDeque<Record>,
+    busy: bool,
+    shutdown: bool,
+    /// Set when the background thread has stopped, including after a sink panicked.
+    exited: bool,
+}
+
+struct Shared {
+    state: Mutex<QueueState>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    idle: Condvar,
+    dropped: Arc<AtomicU64>,
+    unreported: AtomicU64,
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Marks the background thread as exited when it returns or unwinds, so nobody waits for it forever.
+struct ExitGuard(Arc<Shared>);
+
+impl Drop for ExitGuard {
+    fn drop(&mut self) {
+        let mut state = self.0.state();
+        state.exited = true;
+        state.busy = false;
+        drop(state);
+        self.0.idle.notify_all();
+        self.0.not_full.notify_all();
+    }
+}
+
+/// Background thread that owns the writing side of an asynchronous `Log`.
+pub(crate) struct AsyncWorker {
+    shared: Arc<Shared>,
+    config: AsyncConfig,
+    handle: JoinHandle<()>,
+}
+
+impl AsyncWorker {
+    /// Starts the background thread.
+    ///
+    /// `dropped` is owned by the `Log`, so the count survives the worker.
+    pub(crate) fn spawn(
+        sinks: SharedSinks,
+        config: AsyncConfig,
+        dropped: Arc<AtomicU64>,
+    ) -> AsyncWorker {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(QueueState {
+                records: VecDeque::with_capacity(config.capacity),
+                busy: false,
+                shutdown: false,
+                exited: false,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            idle: Condvar::new(),
+            dropped,
+            unreported: AtomicU64::new(0),
+        });
+        let worker_shared = shared.clone();
+        let handle = std::thread::Builder::new()
+            .name("logy".to_string())
+            .spawn(move || run(worker_shared, sinks))
+            .expect("Failed to spawn the logy thread");
+        Self {
+            shared,
+            config,
+            handle,
+        }
+    }
+
+    /// Queues a record, applying the overflow policy if the queue is full.
+    ///
+    /// The record is counted as dropped if the background thread has exited.
+    pub(crate) fn push(&self, record: Record) {
+        let mut state = self.shared.state();
+        if state.records.len() >= self.config.capacity {
+            match self.config.overflow {
+                // The background thread cannot wait for itself, the queue grows instead.
+                OverflowPolicy::Block if on_worker_thread() => {}
+                OverflowPolicy::Block => {
+                    while state.records.len() >= self.config.capacity
+                        && !state.shutdown
+                        && !state.exited
+                    {
+                        state = self
+                            .shared
+                            .not_full
+                            .wait(state)
+                            .unwrap_or_else(PoisonError::into_inner);
+                    }
+                }
+                OverflowPolicy::DropNewest => {
+                    self.count_dropped();
+                    return;
+                }
+                OverflowPolicy::DropOldest => {
+                    state.records.pop_front();
+                    self.count_dropped();
+                }
+            }
+        }
+        if state.exited {
+            self.count_dropped();
+            return;
+        }
+        state.records.push_back(record);
+        self.shared.not_empty.notify_one();
+    }
+
+    /// Blocks until every queued record has been written or the background thread has exited.
+    pub(crate) fn wait_idle(&self) {
+        let mut state = self.shared.state();
+        while (!state.records.is_empty() || state.busy) && !state.exited {
+            state = self
+                .shared
+                .idle
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Writes the remaining records and joins the background thread.
+    pub(crate) fn shutdown(self) {
+        self.shared.state().shutdown = true;
+        self.shared.not_empty.notify_one();
+        self.shared.not_full.notify_all();
+        if self.handle.join().is_err() {
+            eprintln!("The logy thread panicked");
+        }
+    }
+
+    fn count_dropped(&self) {
+        self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+        self.shared.unreported.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn run(shared: Arc<Shared>, sinks: SharedSinks) {
+    ON_WORKER.set(true);
+    let _exit = ExitGuard(shared.clone());
+    loop {
+        let batch: Vec<Record> = {
+            let mut state = shared.state();
+            while state.records.is_empty() && !state.shutdown {
+                state = shared
+                    .not_empty
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+            if state.records.is_empty() {
+                break;
+            }
+            state.busy = true;
+            state.records.drain(..).collect()
+        };
+        shared.not_full.notify_all();
+
+        let mut sinks = sinks.lock().unwrap_or_else(PoisonError::into_inner);
+        let unreported = shared.unreported.swap(0, Ordering::Relaxed);
+        if unreported > 0 {
+            let message = format!("{} log records dropped, the queue was full", unreported);
+            dispatch(
+                &mut sinks,
+                &Record::new(LogLevel::Warn, module_path!(), message),
+            );
+        }
+        for record in &batch {
+            dispatch(&mut sinks, record);
+        }
+        drop(sinks);
+
+        shared.state().busy = false;
+        shared.idle.notify_all();
+    }
+}
@@ -0,0 +1,634 @@
+//! `logy-view` reads text or JSON Lines logs written by logy, filters and highlights them.
+//!
+//! ```text
+//! logy-view [OPTIONS] <FILE>
+//!
+//!   --level <LEVEL>        only show records at least as severe as LEVEL
+//!   --target <PREFIX>      only show records whose target starts with PREFIX (JSON logs)
+//!   --since <TIME>         only show records at or after TIME (RFC 3339 prefix)
+//!   --until <TIME>         only show records at or before TIME (RFC 3339 prefix)
+//!   --field <KEY=VALUE>    only show records with the field, can be repeated
+//!   --grep <TEXT>          only show records whose message contains TEXT, highlighted
+//!   -f, --follow           keep reading as the file grows
+//!   --no-color             never write colors
+//! ```
+
+use logy::{color_enabled, LogLevel, Theme, RESET_COLOR};
+use std::fs::{File, Metadata};
+use std::io::{BufRead, BufReader, ErrorKind, IsTerminal, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread::sleep;
+use std::time::Duration;
+
+const USAGE: &str =
+    "Usage: logy-view [--level LEVEL] [--target PREFIX] [--since TIME] [--until TIME] \
+[--field KEY=VALUE]... [--grep TEXT] [-f|--follow] [--no-color] <FILE>";
+const HIGHLIGHT: &str = "\x1b[7m";
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), String> {
+    let options = Options::parse(std::env::args().skip(1))?;
+    let theme =
+        color_enabled(std::io::stdout().is_terminal() && !options.no_color).then(Theme::default);
+
+    let mut out = std::io::stdout().lock();
+    let mut file = File::open(&options.path).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(file.try_clone().map_err(|e| e.to_string())?);
+    let mut position = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line).map_err(|e| e.to_string())?;
+        if read == 0 || !line.ends_with('\n') && options.follow {
+            if !options.follow {
+                return Ok(());
+            }
+            // Wait for the rest of a partially written line or for new lines.
+            sleep(FOLLOW_INTERVAL);
+            // The path may be missing for a moment while the file is rotated, keep waiting then.
+            if let Ok(current) = std::fs::metadata(&options.path) {
+                let opened = file.metadata().map_err(|e| e.to_string())?;
+                if current.len() < position || file_id(&current) != file_id(&opened) {
+                    // The file was truncated or rotated, start over.
+                    file = File::open(&options.path).map_err(|e| e.to_string())?;
+                    position = 0;
+                }
+            }
+            file.seek(SeekFrom::Start(position))
+                .map_err(|e| e.to_string())?;
+            reader = BufReader::new(file.try_clone().map_err(|e| e.to_string())?);
+            continue;
+        }
+        position += read as u64;
+        if let Some(entry) = Entry::parse(line.trim_end()) {
+            if options.matches(&entry) {
+                let rendered = entry.render(theme.as_ref(), options.grep.as_deref());
+                match writeln!(out, "{}", rendered) {
+                    Ok(()) => {}
+                    // The reader went away, e.g. `logy-view app.log | head`.
+                    Err(e) if e.kind() == ErrorKind::BrokenPipe => return Ok(()),
+                    Err(e) => return Err(e.to_string()),
+                }
+            }
+        }
+    }
+}
+
+/// Identifies a file independently of its path, so a rotated log can be told apart from its successor.
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Identifies a file independently of its path, only the length is compared on this platform.
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Command line options.
+struct Options {
+    path: PathBuf,
+    level: Option<LogLevel>,
+    target: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    fields: Vec<(String, String)>,
+    grep: Option<String>,
+    follow: bool,
+    no_color: bool,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut path = None;
+        let mut options = Options {
+            path: PathBuf::new(),
+            level: None,
+            target: None,
+            since: None,
+            until: None,
+            fields: Vec::new(),
+            grep: None,
+            follow: false,
+            no_color: false,
+        };
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {}\n{}", name, USAGE))
+            };
+            match arg.as_str() {
+                "--level" => options.level = Some(value("--level")?.parse()?),
+                "--target" => options.target = Some(value("--target")?),
+                "--since" => options.since = Some(value("--since")?),
+                "--until" => options.until = Some(value("--until")?),
+                "--field" => {
+                    let field = value("--field")?;
+                    let (key, expected) = field
+                        .split_once('=')
+                        .ok_or_else(|| format!("Expected KEY=VALUE, got: {}", field))?;
+                    options.fields.push((key.to_string(), expected.to_string()));
+                }
+                "--grep" => options.grep = Some(value("--grep")?),
+                "-f" | "--follow" => options.follow = true,
+                "--no-color" => options.no_color = true,
+                "-h" | "--help" => return Err(USAGE.to_string()),
+                other if other.starts_with('-') => {
+                    return Err(format!("Unknown option: {}\n{}", other, USAGE))
+                }
+                other => path = Some(PathBuf::from(other)),
+            }
+        }
+        options.path = path.ok_or_else(|| USAGE.to_string())?;
+        Ok(options)
+    }
+
+    fn matches(&self, entry: &Entry) -> bool {
+        if self.level.is_some_and(|level| entry.level < level) {
+            return false;
+        }
+        if let Some(target) = &self.target {
+            if !entry
+                .target
+                .as_deref()
+                .is_some_and(|t| t.starts_with(target.as_str()))
+            {
+                return false;
+            }
+        }
+        if let Some(since) = &self.since {
+            if entry
+                .time
+                .as_deref()
+                .is_none_or(|time| time < since.as_str())
+            {
+                return false;
+            }
+        }
+        if let Some(until) = &self.until {
+            // Compare only the given prefix, so `--until 2024-06-10T18` includes that whole hour.
+            let included = entry.time.as_deref().is_some_and(|time| {
+                let prefix = time.get(..until.len()).unwrap_or(time);
+                prefix <= until.as_str()
+            });
+            if !included {
+                return false;
+            }
+        }
+        let fields_match = self.fields.iter().all(|(key, expected)| {
+            entry
+                .fields
+                .iter()
+                .any(|(k, value)| k == key && value == expected)
+        });
+        fields_match
+            && self
+                .grep
+                .as_deref()
+                .is_none_or(|text| entry.message.contains(text))
+    }
+}
+
+/// A parsed log line.
+struct Entry {
+    time: Option<String>,
+    elapsed: Option<String>,
+    level: LogLevel,
+    target: Option<String>,
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Entry {
+    fn parse(line: &str) -> Option<Entry> {
+        if line.starts_with('{') {
+            Self::parse_json(line)
+        } else {
+            Self::parse_text(&strip_ansi(line))
+        }
+    }
+
+    /// Parses lines of `JsonFormatter`.
+    fn parse_json(line: &str) -> Option<Entry> {
+        let Json::Object(members) = Json::parse(line)? else {
+            return None;
+        };
+        let text = |key: &str| {
+            members
+                .iter()
+                .find(|(k, _)| k == key)
+                .and_then(|(_, v)| match v {
+                    Json::Str(s) => Some(s.clone()),
+                    Json::Number(n) => Some(n.clone()),
+                    _ => None,
+                })
+        };
+        let fields = match members.iter().find(|(k, _)| k == "fields") {
+            Some((_, Json::Object(fields))) => fields
+                .iter()
+                .map(|(key, value)| (key.clone(), value.to_string()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        Some(Entry {
+            time: text("timestamp"),
+            elapsed: text("elapsed_ms"),
+            level: text("level")?.parse().ok()?,
+            target: text("target"),
+            message: text("message").unwrap_or_default(),
+            fields,
+        })
+    }
+
+    /// Parses lines of `TextFormatter`: `[TIME ][+Nms ][LEVEL]: message key=value...`.
+    fn parse_text(line: &str) -> Option<Entry> {
+        let open = line.find('[')?;
+        let close = open + line[open..].find(']')?;
+        let level = line[open + 1..close].parse().ok()?;
+        let mut time = None;
+        let mut elapsed = None;
+        for token in line[..open].split_whitespace() {
+            match token.strip_prefix('+').and_then(|t| t.strip_suffix("ms")) {
+                Some(ms) => elapsed = Some(ms.to_string()),
+                None => time = Some(token.to_string()),
+            }
+        }
+        let rest = line[close + 1..].trim_start_matches([' ', ':']);
+        // Fields start at the first `key=value` word, a value runs until the next key.
+        let words: Vec<&str> = rest.split(' ').collect();
+        let is_field = |word: &str| {
+            word.split_once('=').is_some_and(|(key, _)| {
+                !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_')
+            })
+        };
+        let first_field = words
+            .iter()
+            .position(|w| is_field(w))
+            .unwrap_or(words.len());
+        let mut fields: Vec<(String, String)> = Vec::new();
+        for word in &words[first_field..] {
+            match word.split_once('=') {
+                Some((key, value)) if is_field(word) => {
+                    fields.push((key.to_string(), value.to_string()))
+                }
+                _ => {
+                    if let Some((_, value)) = fields.last_mut() {
+                        value.push(' ');
+                        value.push_str(word);
+                    }
+                }
+            }
+        }
+        let message = words[..first_field].join(" ");
+        Some(Entry {
+            time,
+            elapsed,
+            level,
+            target: None,
+            message,
+            fields,
+        })
+    }
+
+    fn render(&self, theme: Option<&Theme>, highlight: Option<&str>) -> String {
+        let mut out = String::new();
+        if let Some(theme) = theme {
+            out.push_str(theme.color(self.level));
+        }
+        for part in [
+            &self.time,
+            &self.elapsed.as_ref().map(|e| format!("+{}ms", e)),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            out.push_str(part);
+            out.push(' ');
+        }
+        out.push_str(&format!("{:>5} ", self.level));
+        if let Some(target) = &self.target {
+            out.push_str(target);
+            out.push_str(": ");
+        }
+        match (theme, highlight) {
+            (Some(theme), Some(text)) if !text.is_empty() => {
+                let color = theme.color(self.level);
+                let marked = format!("{}{}{}{}", HIGHLIGHT, text, RESET_COLOR, color);
+                out.push_str(&self.message.replace(text, &marked));
+            }
+            _ => out.push_str(&self.message),
+        }
+        for (key, value) in &self.fields {
+            out.push_str(&format!(" {}={}", key, value));
+        }
+        if theme.is_some() {
+            out.push_str(RESET_COLOR);
+        }
+        out
+    }
+}
+
+/// Removes ANSI escape sequences like `\x1b[91m`.
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Minimal JSON value, enough to read the output of `JsonFormatter`.
+enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn parse(text: &str) -> Option<Json> {
+        let mut parser = JsonParser {
+            chars: text.chars().collect(),
+            position: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        (parser.position == parser.chars.len()).then_some(value)
+    }
+}
+
+impl std::fmt::Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) | Json::Str(n) => f.write_str(n),
+            Json::Array(items) => {
+                let items: Vec<String> = items.iter().map(Json::to_string).collect();
+                write!(f, "[{}]", items.join(","))
+            }
+            Json::Object(_) => f.write_str("{...}"),
+        }
+    }
+}
+
+struct JsonParser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl JsonParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, word: &str) -> Option<()> {
+        word.chars().all(|c| self.next() == Some(c)).then_some(())
+    }
+
+    fn value(&mut self) -> Option<Json> {
+        self.skip_whitespace();
+        match self.peek()? {
+            '{' => self.object(),
+            '[' => self.array(),
+            '"' => self.string().map(Json::Str),
+            't' => self.expect("true").map(|_| Json::Bool(true)),
+            'f' => self.expect("false").map(|_| Json::Bool(false)),
+            'n' => self.expect("null").map(|_| Json::Null),
+            _ => self.number(),
+        }
+    }
+
+    fn object(&mut self) -> Option<Json> {
+        self.next();
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.next();
+            return Some(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(":")?;
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.next()? {
+                ',' => continue,
+                '}' => return Some(Json::Object(members)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn array(&mut self) -> Option<Json> {
+        self.next();
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.next();
+            return Some(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.next()? {
+                ',' => continue,
+                ']' => return Some(Json::Array(items)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if self.next()? != '"' {
+            return None;
+        }
+        let mut out = String::new();
+        loop {
+            match self.next()? {
+                '"' => return Some(out),
+                '\\' => match self.next()? {
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    'b' => out.push('\u{8}'),
+                    'f' => out.push('\u{c}'),
+                    'u' => {
+                        let code: String = (0..4).filter_map(|_| self.next()).collect();
+                        let code = u32::from_str_radix(&code, 16).ok()?;
+                        out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    c => out.push(c),
+                },
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<Json> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            self.position += 1;
+        }
+        (self.position > start)
+            .then(|| Json::Number(self.chars[start..self.position].iter().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(args: &[&str]) -> Options {
+        Options::parse(
+            args.iter()
+                .map(|a| a.to_string())
+                .chain(["app.log".to_string()]),
+        )
+        .unwrap()
+    }
+
+    fn entry(time: &str, level: LogLevel, message: &str) -> Entry {
+        Entry {
+            time: Some(time.to_string()),
+            elapsed: None,
+            level,
+            target: Some("buji::core".to_string()),
+            message: message.to_string(),
+            fields: vec![("frame".to_string(), "42".to_string())],
+        }
+    }
+
+    #[test]
+    fn parse_text_reads_time_elapsed_level_message_and_fields() {
+        let entry = Entry::parse_text(
+            "2024-06-10T18:30:05.123Z +1532ms [WARN]: Pre Exit... frame=42 path=a b",
+        )
+        .unwrap();
+        assert_eq!(entry.time.as_deref(), Some("2024-06-10T18:30:05.123Z"));
+        assert_eq!(entry.elapsed.as_deref(), Some("1532"));
+        assert_eq!(entry.level, LogLevel::Warn);
+        assert_eq!(entry.message, "Pre Exit...");
+        assert_eq!(
+            entry.fields,
+            vec![
+                ("frame".to_string(), "42".to_string()),
+                ("path".to_string(), "a b".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_text_rejects_lines_without_level() {
+        assert!(Entry::parse_text("no level here").is_none());
+        assert!(Entry::parse_text("[LOUD]: unknown level").is_none());
+    }
+
+    #[test]
+    fn parse_json_reads_formatter_output() {
+        let entry = Entry::parse_json(
+            r#"{"timestamp":"2024-06-10T18:30:05.123Z","elapsed_ms":1532,"level":"WARN","target":"buji::core","message":"Pre \"Exit\"","fields":{"frame":42,"tags":["a","b"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.time.as_deref(), Some("2024-06-10T18:30:05.123Z"));
+        assert_eq!(entry.elapsed.as_deref(), Some("1532"));
+        assert_eq!(entry.level, LogLevel::Warn);
+        assert_eq!(entry.target.as_deref(), Some("buji::core"));
+        assert_eq!(entry.message, "Pre \"Exit\"");
+        assert_eq!(
+            entry.fields,
+            vec![
+                ("frame".to_string(), "42".to_string()),
+                ("tags".to_string(), "[a,b]".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_json_rejects_invalid_lines() {
+        assert!(Entry::parse_json(r#"{"level":"WARN""#).is_none());
+        assert!(Entry::parse_json(r#"{"message":"no level"}"#).is_none());
+        assert!(Entry::parse_json(r#"["WARN"]"#).is_none());
+    }
+
+    #[test]
+    fn matches_level_target_field_and_grep() {
+        let entry = entry("2024-06-10T18:30:05Z", LogLevel::Warn, "Pre Exit...");
+        assert!(options(&[]).matches(&entry));
+        assert!(options(&["--level", "warn"]).matches(&entry));
+        assert!(!options(&["--level", "error"]).matches(&entry));
+        assert!(options(&["--target", "buji"]).matches(&entry));
+        assert!(!options(&["--target", "logy"]).matches(&entry));
+        assert!(options(&["--field", "frame=42"]).matches(&entry));
+        assert!(!options(&["--field", "frame=43"]).matches(&entry));
+        assert!(options(&["--grep", "Exit"]).matches(&entry));
+        assert!(!options(&["--grep", "Enter"]).matches(&entry));
+    }
+
+    #[test]
+    fn matches_time_range_by_prefix() {
+        let entry = entry("2024-06-10T18:30:05Z", LogLevel::Info, "tick");
+        assert!(options(&["--since", "2024-06-10T18"]).matches(&entry));
+        assert!(!options(&["--since", "2024-06-10T19"]).matches(&entry));
+        assert!(options(&["--until", "2024-06-10T18"]).matches(&entry));
+        assert!(!options(&["--until", "2024-06-10T17"]).matches(&entry));
+    }
+
+    #[test]
+    fn matches_does_not_panic_on_multibyte_time() {
+        // The `--until` length ends inside the last character of the time.
+        let entry = entry("2024-06-10T18:30:05Zé", LogLevel::Info, "tick");
+        assert!(options(&["--until", "2024-06-10T18:30:06Z_"]).matches(&entry));
+        assert!(!options(&["--until", "2024-06-10T18:30:04Z_"]).matches(&entry));
+    }
+
+    #[test]
+    fn strip_ansi_removes_color_sequences() {
+        assert_eq!(
+            strip_ansi("\x1b[91m[ERROR]: failed\x1b[0m"),
+            "[ERROR]: failed"
+        );
+        assert_eq!(strip_ansi("plain é text"), "plain é text");
+    }
+}
@@ -0,0 +1,168 @@
+use crate::log_level::LogLevel;
+use crate::record::Record;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type Records = Rc<RefCell<Vec<Record>>>;
+
+thread_local! {
+    /// Captures that are active on the current thread, innermost last.
+    static CAPTURES: RefCell<Vec<Records>> = const { RefCell::new(Vec::new()) };
+}
+
+/// An in-memory logger for tests that captures every record emitted on the current thread.
+///
+/// While a capture is alive, the logy macros on the same thread are enabled for all
+/// levels (even without a global logger) and their records are stored in the capture.
+/// Records still reach the global logger if it is set and its filter allows them.
+/// Captures are scoped to the thread that started them, so tests running in
+/// parallel do not see each other's records.
+///
+/// # Example
+///
+/// ```rust
+/// use logy::{assert_logged, lwarn, LogCapture, LogLevel};
+///
+/// let capture = LogCapture::start();
+/// lwarn!("Tried to load an invalid texture: {}", 42);
+///
+/// assert_logged!(LogLevel::Warn, contains "invalid texture");
+/// assert_logged!(capture, LogLevel::Warn, contains "42");
+/// assert_eq!(capture.records().len(), 1);
+/// ```
+pub struct LogCapture {
+    records: Records,
+    /// `false` for the temporary handles of `with_current`, which must not end the capture.
+    scoped: bool,
+}
+
+impl LogCapture {
+    /// Starts capturing the records of the current thread until the returned value is dropped.
+    pub fn start() -> LogCapture {
+        let records = Records::default();
+        CAPTURES.with_borrow_mut(|captures| captures.push(records.clone()));
+        Self {
+            records,
+            scoped: true,
+        }
+    }
+
+    /// Returns a copy of every captured record, oldest first.
+    pub fn records(&self) -> Vec<Record> {
+        self.records.borrow().clone()
+    }
+
+    /// Returns the messages of every captured record, oldest first.
+    pub fn messages(&self) -> Vec<String> {
+        self.records
+            .borrow()
+            .iter()
+            .map(|record| record.message.clone())
+            .collect()
+    }
+
+    /// Returns the first captured record with the given level whose message contains `needle`.
+    pub fn find(&self, level: LogLevel, needle: &str) -> Option<Record> {
+        self.records
+            .borrow()
+            .iter()
+            .find(|record| record.level == level && record.message.contains(needle))
+            .cloned()
+    }
+
+    /// Counts the captured records with the given level whose message contains `needle`.
+    pub fn count(&self, level: LogLevel, needle: &str) -> usize {
+        self.records
+            .borrow()
+            .iter()
+            .filter(|record| record.level == level && record.message.contains(needle))
+            .count()
+    }
+
+    /// Removes every captured record.
+    pub fn clear(&self) {
+        self.records.borrow_mut().clear();
+    }
+
+    /// Panics unless a record with the given level whose message contains `needle` was captured.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the list of captured records if no record matches.
+    #[track_caller]
+    pub fn assert_logged(&self, level: LogLevel, needle: &str) {
+        if self.find(level, needle).is_none() {
+            panic!(
+                "no {} record containing {:?} was logged, captured records:\n{}",
+                level,
+                needle,
+                self.describe()
+            );
+        }
+    }
+
+    /// Panics if a record with the given level whose message contains `needle` was captured.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the matching record if one was captured.
+    #[track_caller]
+    pub fn assert_not_logged(&self, level: LogLevel, needle: &str) {
+        if let Some(record) = self.find(level, needle) {
+            panic!(
+                "unexpected {} record containing {:?} was logged: {}",
+                level, needle, record.message
+            );
+        }
+    }
+
+    /// Runs `f` with the innermost capture of the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no capture is active on the current thread.
+    #[track_caller]
+    pub fn with_current<R>(f: impl FnOnce(&LogCapture) -> R) -> R {
+        let records = CAPTURES
+            .with_borrow(|captures| captures.last().cloned())
+            .expect("no LogCapture is active on this thread, call LogCapture::start first");
+        f(&LogCapture {
+            records,
+            scoped: false,
+        })
+    }
+
+    fn describe(&self) -> String {
+        self.records
+            .borrow()
+            .iter()
+            .map(|record| format!("  {} {}: {}", record.level, record.target, record.message))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Drop for LogCapture {
+    fn drop(&mut self) {
+        if !self.scoped {
+            return;
+        }
+        CAPTURES.with_borrow_mut(|captures| {
+            captures.retain(|records| !Rc::ptr_eq(records, &self.records));
+        });
+    }
+}
+
+/// Returns `true` if a capture is active on the current thread.
+pub(crate) fn is_active() -> bool {
+    CAPTURES.with_borrow(|captures| !captures.is_empty())
+}
+
+/// Stores a copy of the record in every capture that is active on the current thread.
+pub(crate) fn capture(record: &Record) {
+    CAPTURES.with_borrow(|captures| {
+        for records in captures {
+            records.borrow_mut().push(record.clone());
+        }
+    });
+}
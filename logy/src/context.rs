@@ -0,0 +1,84 @@
+use crate::value::Value;
+use std::cell::RefCell;
+
+thread_local! {
+    static CONTEXT: RefCell<Vec<(String, Value)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Thread local key-value context (MDC) inherited by every record created on the thread.
+///
+/// Fields pushed with `LogContext::push` (or the `lcontext!` macro) are attached to all
+/// records created on the same thread until the returned guard is dropped. Inner scopes
+/// override outer fields with the same key.
+///
+/// # Example
+///
+/// ```rust
+/// use logy::{lcontext, LogContext};
+///
+/// let _frame = lcontext!(frame = 42u64, state = "Running");
+/// {
+///     let _scene = LogContext::push("scene", "level_1");
+///     assert_eq!(LogContext::fields().len(), 3);
+/// }
+/// assert_eq!(LogContext::fields().len(), 2);
+/// ```
+pub struct LogContext;
+
+impl LogContext {
+    /// Pushes a field onto the context of the current thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Name of the field.
+    /// * `value` - Anything convertible to a field `Value`.
+    ///
+    /// # Returns
+    ///
+    /// A guard that removes the field (and anything pushed after it) when dropped.
+    pub fn push(key: &str, value: impl Into<Value>) -> ContextGuard {
+        CONTEXT.with_borrow_mut(|context| {
+            let guard = ContextGuard { len: context.len() };
+            context.push((key.to_string(), value.into()));
+            guard
+        })
+    }
+
+    /// Returns the current context fields of the thread, outermost first.
+    pub fn fields() -> Vec<(String, Value)> {
+        CONTEXT.with_borrow(|context| {
+            let mut fields: Vec<(String, Value)> = Vec::with_capacity(context.len());
+            for (key, value) in context.iter() {
+                match fields.iter_mut().find(|(k, _)| k == key) {
+                    Some(field) => field.1 = value.clone(),
+                    None => fields.push((key.clone(), value.clone())),
+                }
+            }
+            fields
+        })
+    }
+}
+
+/// Removes context fields when dropped. Created by `LogContext::push`.
+///
+/// Guards are expected to be dropped in reverse order of creation, dropping an outer
+/// guard also removes the fields of inner ones.
+#[must_use = "the context fields are removed when the guard is dropped"]
+pub struct ContextGuard {
+    len: usize,
+}
+
+impl ContextGuard {
+    /// Returns a guard that removes nothing, used to combine guards in `lcontext!`.
+    pub fn current() -> ContextGuard {
+        Self {
+            len: CONTEXT.with_borrow(Vec::len),
+        }
+    }
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        CONTEXT.with_borrow_mut(|context| context.truncate(self.len));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::log_level::LogLevel;
+
+/// Name of the environment variable read by `LogController::init_logger`.
+///
+/// The value uses the same syntax as `Filter::parse`, e.g. `LOGY=info,buji::core=warn`.
+pub const LOGY_ENV: &str = "LOGY";
+
+/// Decides which records are enabled, globally and per target.
+///
+/// A target is the module path of the call site (e.g. `buji::core`). A directive for
+/// `buji` also applies to `buji::core` unless a more specific directive exists.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    level: LogLevel,
+    targets: Vec<(String, LogLevel)>,
+}
+
+impl Filter {
+    /// Creates a filter that enables every record at least as severe as `level`.
+    pub fn new(level: LogLevel) -> Filter {
+        Self {
+            level,
+            targets: Vec::new(),
+        }
+    }
+
+    /// Adds or replaces the level of a target and its sub modules.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Module path prefix like `buji::asset_server`.
+    /// * `level` - Minimum level for records of that target.
+    pub fn with_target(mut self, target: &str, level: LogLevel) -> Filter {
+        self.targets.retain(|(t, _)| t != target);
+        self.targets.push((target.to_string(), level));
+        self
+    }
+
+    /// Parses a comma separated list of directives.
+    ///
+    /// A bare level sets the global threshold, `target=level` sets the level of a target.
+    /// Example: `"info,buji::core=warn,buji::asset_server=debug"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if a level name is unknown or a directive is malformed.
+    pub fn parse(spec: &str) -> Result<Filter, String> {
+        let mut filter = Filter::default();
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    let target = target.trim();
+                    if target.is_empty() {
+                        return Err(format!("Missing target in directive: {}", directive));
+                    }
+                    filter = filter.with_target(target, level.parse()?);
+                }
+                None => filter.level = directive.parse()?,
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Reads and parses the `LOGY` environment variable.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the variable is not set, otherwise the parse result.
+    pub fn from_env() -> Option<Result<Filter, String>> {
+        std::env::var(LOGY_ENV)
+            .ok()
+            .map(|spec| Filter::parse(&spec))
+    }
+
+    /// Checks whether a record of `level` coming from `target` passes the filter.
+    pub fn enabled(&self, level: LogLevel, target: &str) -> bool {
+        level >= self.level_for(target)
+    }
+
+    /// Returns the least severe level that any target can enable.
+    pub fn max_level(&self) -> LogLevel {
+        self.targets
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.level, LogLevel::min)
+    }
+
+    fn level_for(&self, target: &str) -> LogLevel {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| {
+                target == prefix
+                    || (target.starts_with(prefix.as_str())
+                        && target[prefix.len()..].starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.level, |(_, level)| *level)
+    }
+}
+
+impl Default for Filter {
+    /// Enables `Info` and more severe records for all targets.
+    fn default() -> Self {
+        Self::new(LogLevel::Info)
+    }
+}
@@ -0,0 +1,172 @@
+use crate::json;
+use crate::log_level::LogLevel;
+use crate::record::Record;
+use crate::theme::{color_enabled, Theme, RESET_COLOR};
+use crate::time::Timestamp;
+use std::fmt::Write;
+
+/// Turns a `Record` into the text that a sink writes to its target.
+pub trait Formatter: Send {
+    /// Formats the record as a single line, including the trailing line break.
+    fn format(&self, record: &Record) -> String;
+}
+
+/// Human readable line formatter, optionally colored with ANSI escape codes.
+///
+/// Produces lines like `[WARN] : Video sub system is ready`.
+/// Fields are appended as `key=value` pairs. Timestamps are written in front of the
+/// level when enabled with `with_timestamp`. Use `TemplateFormatter` for custom layouts.
+pub struct TextFormatter {
+    theme: Option<Theme>,
+    timestamp: Timestamp,
+}
+
+impl TextFormatter {
+    /// Creates a formatter that wraps every line in the ANSI color of its level.
+    pub fn colored() -> TextFormatter {
+        Self {
+            theme: Some(Theme::default()),
+            timestamp: Timestamp::None,
+        }
+    }
+
+    /// Creates a formatter without any escape codes, suitable for files.
+    pub fn plain() -> TextFormatter {
+        Self {
+            theme: None,
+            timestamp: Timestamp::None,
+        }
+    }
+
+    /// Creates a colored or plain formatter depending on the target and the environment.
+    ///
+    /// See `color_enabled` for the rules (`NO_COLOR`, `FORCE_COLOR`, terminal detection).
+    ///
+    /// # Arguments
+    ///
+    /// * `is_terminal` - Whether the target is a terminal.
+    pub fn auto(is_terminal: bool) -> TextFormatter {
+        if color_enabled(is_terminal) {
+            Self::colored()
+        } else {
+            Self::plain()
+        }
+    }
+
+    /// Colors every line with the given theme.
+    pub fn with_theme(mut self, theme: Theme) -> TextFormatter {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Sets which timestamps are written in front of each line.
+    pub fn with_timestamp(mut self, timestamp: Timestamp) -> TextFormatter {
+        self.timestamp = timestamp;
+        self
+    }
+}
+
+impl Default for TextFormatter {
+    fn default() -> Self {
+        Self::colored()
+    }
+}
+
+impl Formatter for TextFormatter {
+    fn format(&self, record: &Record) -> String {
+        let label = match record.level {
+            LogLevel::Error => "[ERROR]:",
+            LogLevel::Warn => "[WARN] :",
+            LogLevel::Info => "[INFO] :",
+            LogLevel::Debug => "[DEBUG]:",
+            LogLevel::Trace => "[TRACE]:",
+        };
+        let mut prefix = String::new();
+        if self.timestamp.wall_clock() {
+            let _ = write!(prefix, "{} ", record.rfc3339());
+        }
+        if self.timestamp.elapsed() {
+            let _ = write!(prefix, "+{}ms ", record.elapsed_millis());
+        }
+        let mut message = record.message.clone();
+        for (key, value) in &record.fields {
+            let _ = write!(message, " {}={}", key, value);
+        }
+        if let Some(theme) = &self.theme {
+            let color = theme.color(record.level);
+            format!("{}{}{} {}{}\n", color, prefix, label, message, RESET_COLOR)
+        } else {
+            format!("{}{} {}\n", prefix, label, message)
+        }
+    }
+}
+
+/// Machine readable formatter that writes one JSON object per line (JSON Lines).
+///
+/// Produces lines like
+/// `{"timestamp":"2024-06-10T18:30:05.123Z","elapsed_ms":1532,"level":"WARN","target":"buji::core","message":"Pre Exit...","fields":{"frame":42}}`.
+/// `timestamp` is the RFC 3339 wall clock time, `elapsed_ms` the milliseconds since the logger started.
+/// `file` and `line` are included when known.
+pub struct JsonFormatter {
+    timestamp: Timestamp,
+}
+
+impl JsonFormatter {
+    /// Creates a new JSON Lines formatter that writes both timestamps.
+    pub fn new() -> JsonFormatter {
+        Self {
+            timestamp: Timestamp::Both,
+        }
+    }
+
+    /// Sets which timestamps are written for each record.
+    pub fn with_timestamp(mut self, timestamp: Timestamp) -> JsonFormatter {
+        self.timestamp = timestamp;
+        self
+    }
+}
+
+impl Default for JsonFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter for JsonFormatter {
+    fn format(&self, record: &Record) -> String {
+        let mut out = String::with_capacity(128);
+        out.push('{');
+        if self.timestamp.wall_clock() {
+            out.push_str("\"timestamp\":");
+            json::push_str(&mut out, &record.rfc3339());
+            out.push(',');
+        }
+        if self.timestamp.elapsed() {
+            let _ = write!(out, "\"elapsed_ms\":{},", record.elapsed_millis());
+        }
+        out.push_str("\"level\":");
+        json::push_str(&mut out, record.level.as_str());
+        out.push_str(",\"target\":");
+        json::push_str(&mut out, &record.target);
+        out.push_str(",\"message\":");
+        json::push_str(&mut out, &record.message);
+        if let Some(file) = &record.file {
+            out.push_str(",\"file\":");
+            json::push_str(&mut out, file);
+        }
+        if let Some(line) = record.line {
+            let _ = write!(out, ",\"line\":{}", line);
+        }
+        out.push_str(",\"fields\":{");
+        for (i, (key, value)) in record.fields.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            json::push_str(&mut out, key);
+            out.push(':');
+            json::push_value(&mut out, value);
+        }
+        out.push_str("}}\n");
+        out
+    }
+}
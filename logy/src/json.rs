@@ -0,0 +1,32 @@
+use crate::value::Value;
+use std::fmt::Write;
+
+/// Appends `value` to `out` as a quoted and escaped JSON string.
+pub(crate) fn push_str(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Appends a field value to `out` as a JSON value.
+pub(crate) fn push_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Str(v) => push_str(out, v),
+        Value::Float(v) if !v.is_finite() => out.push_str("null"),
+        v => {
+            let _ = write!(out, "{}", v);
+        }
+    }
+}
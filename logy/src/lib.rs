@@ -1,40 +1,260 @@
+mod async_log;
+mod capture;
+mod context;
+mod filter;
+mod formatter;
+mod json;
 mod log;
+#[cfg(feature = "log")]
+mod log_bridge;
 mod log_controller;
 mod log_level;
-mod mock;
+mod panic_hook;
+mod record;
+mod ring_buffer;
+mod rotating_file;
+mod sink;
+mod span;
+mod syslog;
+mod template;
+mod theme;
+mod throttle;
+mod time;
+mod value;
 
+pub use crate::log::Log;
+pub use async_log::{AsyncConfig, OverflowPolicy};
+pub use capture::LogCapture;
+pub use context::*;
+pub use filter::*;
+pub use formatter::*;
+#[cfg(feature = "log")]
+pub use log_bridge::*;
 pub use log_controller::*;
 pub use log_level::*;
-pub use mock::*;
+pub use panic_hook::CrashReportConfig;
+pub use record::*;
+pub use ring_buffer::*;
+pub use rotating_file::*;
+pub use sink::*;
+pub use span::{chrome_trace_json, write_chrome_trace, Span, SpanEvent};
+pub use syslog::*;
+pub use template::*;
+pub use theme::*;
+pub use time::{format_rfc3339, Timestamp};
+pub use value::*;
 
-use crate::log::Log;
 use lazy_static::lazy_static;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
 
 lazy_static! {
-    static ref LOGGER: Mutex<Option<Arc<Mutex<Log<std::io::Stdout>>>>> = Mutex::new(None);
+    static ref LOGGER: Mutex<Option<Arc<Mutex<Log>>>> = Mutex::new(None);
+    static ref FILTER: RwLock<Filter> = RwLock::new(Filter::default());
+    /// Reference point of `Record::elapsed`, set when the first logger is installed.
+    static ref START: Instant = Instant::now();
+    static ref RING_BUFFER: Mutex<Option<RingBuffer>> = Mutex::new(None);
 }
 
-/// A macro for logging messages using the provided logger if available.
+/// Least severe level enabled by `FILTER`, checked without locking.
+static MAX_LEVEL: AtomicUsize = AtomicUsize::new(LogLevel::Info as usize);
+
+/// Whether `LOGGER` holds a logger, checked without locking.
+static LOGGER_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// A macro for logging a formatted message at the given level.
 ///
-/// This macro checks if the logger exists
-/// If so writes the log message to the target.
-/// If no logger is provided the macro does nothing.
+/// The call site's `module_path!`, `file!` and `line!` are captured into the record.
+/// The format arguments are only evaluated if the level is enabled for the calling
+/// module and a logger is set, otherwise the macro does nothing.
 ///
 /// # Arguments
 ///
-/// * `$log_level`: The log level to use (`LogLevel::Error`, `LogLevel::Warn`, `LogLevel::Info`).
-/// * `$message`: The message to log, as a string slice.
+/// * `$log_level`: The log level to use (`LogLevel::Error`, `LogLevel::Warn`, `LogLevel::Info`, `LogLevel::Debug`, `LogLevel::Trace`).
+/// * `$key = $value`: Optional key-value fields, separated from the message by `;`.
+///   Values can be anything convertible to `Value` (strings, integers, floats, booleans).
+/// * `$arg`: A format string and its arguments, as accepted by `format!`.
 ///
 /// # Panics
 ///
 /// This macro does not panic.
+///
+/// # Example
+///
+/// ```rust
+/// use logy::{llog, LogLevel};
+///
+/// let source_path = "assets/tiles.png";
+/// llog!(LogLevel::Warn, "source_path: {}", source_path);
+/// llog!(LogLevel::Info, frame = 42, asset = source_path; "texture loaded");
+/// ```
+#[macro_export]
+macro_rules! llog {
+    ($log_level:expr, $($key:ident = $value:expr),+ ; $($arg:tt)+) => {{
+        let log_level = $log_level;
+        if let Some(logger_enabled) =
+            $crate::LogController::__private_enabled(log_level, module_path!())
+        {
+            $crate::LogController::__private_log(
+                &$crate::Record::new(log_level, module_path!(), format!($($arg)+))
+                    .with_location(file!(), line!())
+                    $(.with_field(stringify!($key), $value))+,
+                logger_enabled,
+            );
+        }
+    }};
+    ($log_level:expr, $($arg:tt)+) => {{
+        let log_level = $log_level;
+        if let Some(logger_enabled) =
+            $crate::LogController::__private_enabled(log_level, module_path!())
+        {
+            $crate::LogController::__private_log(
+                &$crate::Record::new(log_level, module_path!(), format!($($arg)+))
+                    .with_location(file!(), line!()),
+                logger_enabled,
+            );
+        }
+    }};
+}
+
+/// Logs a formatted message at `LogLevel::Error`. See `llog!`.
+#[macro_export]
+macro_rules! lerror {
+    ($($arg:tt)+) => {
+        $crate::llog!($crate::LogLevel::Error, $($arg)+)
+    };
+}
+
+/// Logs a formatted message at `LogLevel::Warn`. See `llog!`.
+#[macro_export]
+macro_rules! lwarn {
+    ($($arg:tt)+) => {
+        $crate::llog!($crate::LogLevel::Warn, $($arg)+)
+    };
+}
+
+/// Logs a formatted message at `LogLevel::Info`. See `llog!`.
 #[macro_export]
 macro_rules! linfo {
-    ($log_level:expr,$message:expr) => {
-        if let Some(logger) = LogController::get_logger() {
-            let mut logger_ref = logger.lock().unwrap();
-            logger_ref.write($log_level, $message)
+    ($($arg:tt)+) => {
+        $crate::llog!($crate::LogLevel::Info, $($arg)+)
+    };
+}
+
+/// Logs a formatted message at `LogLevel::Debug`. See `llog!`.
+#[macro_export]
+macro_rules! ldebug {
+    ($($arg:tt)+) => {
+        $crate::llog!($crate::LogLevel::Debug, $($arg)+)
+    };
+}
+
+/// Logs a formatted message at `LogLevel::Trace`. See `llog!`.
+#[macro_export]
+macro_rules! ltrace {
+    ($($arg:tt)+) => {
+        $crate::llog!($crate::LogLevel::Trace, $($arg)+)
+    };
+}
+
+/// Pushes key-value fields onto the thread local log context until the returned guard is dropped.
+///
+/// Every record created on the thread while the guard is alive carries the fields. See `LogContext`.
+///
+/// # Example
+///
+/// ```rust
+/// use logy::lcontext;
+///
+/// let frame = 42u64;
+/// let _context = lcontext!(frame = frame, state = "Running");
+/// ```
+#[macro_export]
+macro_rules! lcontext {
+    ($($key:ident = $value:expr),+ $(,)?) => {{
+        let guard = $crate::ContextGuard::current();
+        $(std::mem::forget($crate::LogContext::push(stringify!($key), $value));)+
+        guard
+    }};
+}
+
+/// Opens a profiling span that lasts until the returned guard is dropped.
+///
+/// Spans record their enter and exit times, nesting depth and thread while profiling
+/// is enabled with `LogController::start_profiling`. Otherwise they cost a single atomic
+/// load and the name arguments are not evaluated.
+///
+/// # Example
+///
+/// ```rust
+/// use logy::{lspan, LogController};
+///
+/// LogController::start_profiling();
+/// {
+///     let _frame = lspan!("frame");
+///     let _update = lspan!("update {}", "player");
+/// }
+/// let events = LogController::stop_profiling();
+/// assert_eq!(events.len(), 2);
+/// ```
+#[macro_export]
+macro_rules! lspan {
+    ($name:literal) => {
+        $crate::Span::enter($name, module_path!())
+    };
+    ($($arg:tt)+) => {
+        if $crate::Span::enabled() {
+            $crate::Span::enter(format!($($arg)+), module_path!())
+        } else {
+            $crate::Span::disabled()
         }
     };
 }
+
+/// Asserts that the current thread logged a record with the given level whose message contains a text.
+///
+/// Requires an active `LogCapture`. Without an explicit capture the innermost capture
+/// of the current thread is used.
+///
+/// # Example
+///
+/// ```rust
+/// use logy::{assert_logged, assert_not_logged, lwarn, LogCapture, LogLevel};
+///
+/// let capture = LogCapture::start();
+/// lwarn!("Tried to load an invalid texture");
+///
+/// assert_logged!(LogLevel::Warn, contains "invalid texture");
+/// assert_not_logged!(capture, LogLevel::Error, contains "texture");
+/// ```
+///
+/// # Panics
+///
+/// Panics if no matching record was captured or no capture is active.
+#[macro_export]
+macro_rules! assert_logged {
+    ($log_level:expr, contains $needle:expr) => {
+        $crate::LogCapture::with_current(|capture| capture.assert_logged($log_level, $needle))
+    };
+    ($capture:expr, $log_level:expr, contains $needle:expr) => {
+        $capture.assert_logged($log_level, $needle)
+    };
+}
+
+/// Asserts that the current thread did not log a record with the given level whose message contains a text.
+///
+/// See `assert_logged!`.
+///
+/// # Panics
+///
+/// Panics if a matching record was captured or no capture is active.
+#[macro_export]
+macro_rules! assert_not_logged {
+    ($log_level:expr, contains $needle:expr) => {
+        $crate::LogCapture::with_current(|capture| capture.assert_not_logged($log_level, $needle))
+    };
+    ($capture:expr, $log_level:expr, contains $needle:expr) => {
+        $capture.assert_not_logged($log_level, $needle)
+    };
+}
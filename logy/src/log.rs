@@ -1,18 +1,44 @@
+use crate::async_log::{self, AsyncConfig, AsyncWorker};
 use crate::log_level::LogLevel;
+use crate::record::Record;
+use crate::sink::{Sink, WriterSink};
+use crate::throttle::{Deduplicator, RateLimiter};
 use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
-/// The main logger object that writes log messages to the specified target.
+/// A sink registered on the logger together with the minimum level it accepts.
+pub(crate) struct SinkEntry {
+    min_level: LogLevel,
+    sink: Box<dyn Sink>,
+}
+
+/// Sinks of a logger, shared with the background thread in asynchronous mode.
+pub(crate) type SharedSinks = Arc<Mutex<Vec<SinkEntry>>>;
+
+/// The main logger object that dispatches log records to its sinks.
 ///
-/// The `Log` object uses the `Write` trait to send logs to a target(example: `stdout` or a file)
+/// Several sinks can be active at once (example: colored `stdout` and a plain text file),
+/// each with its own minimum level and formatter.
 ///
-/// # Generic Type Parameter
+/// By default records are written synchronously by the calling thread. After
+/// `set_async` they are queued and written by a dedicated background thread.
 ///
-/// * `W` - An `Write` trait implementation which is where the log messages will be written.
-pub struct Log<W: Write> {
-    target: W,
+/// Repeated records can be collapsed with `set_deduplicate` and noisy call sites
+/// throttled with `set_rate_limit`.
+#[derive(Default)]
+pub struct Log {
+    sinks: SharedSinks,
+    worker: Option<AsyncWorker>,
+    dedup: Option<Deduplicator>,
+    rate_limit: Option<RateLimiter>,
+    /// Records dropped by every background thread so far, kept after `shutdown`.
+    dropped: Arc<AtomicU64>,
 }
-impl<W: Write> Log<W> {
-    /// Creates a new Log object.
+
+impl Log {
+    /// Creates a new Log object with a single text sink, see `WriterSink::new`.
+    /// The sink accepts every level, the global filter decides what is written.
     ///
     /// # Arguments
     ///
@@ -21,31 +47,212 @@ impl<W: Write> Log<W> {
     /// # Returns
     ///
     /// A new instance of the `Log` object.
-    pub fn new(target: W) -> Log<W> {
-        Self { target }
+    pub fn new<W: Write + Send + 'static>(target: W) -> Log {
+        let mut log = Self::default();
+        log.add_sink(WriterSink::new(target), LogLevel::Trace);
+        log
     }
 
-    /// Writes a log message to the target with the specified log level.
-    /// Target is Write trait implementation. Something like that `std::io::stdout`
+    /// Registers a new sink.
     ///
     /// # Arguments
     ///
-    /// * `log_level` - The level of the log message (e.g., `Error`, `Warn`, `Info`).
-    /// * `message` - The actual log message to be written.
+    /// * `sink` - The sink that receives records.
+    /// * `min_level` - Records less severe than this level are not sent to the sink.
+    pub fn add_sink<S: Sink + 'static>(&mut self, sink: S, min_level: LogLevel) {
+        self.sinks().push(SinkEntry {
+            min_level,
+            sink: Box::new(sink),
+        });
+    }
+
+    /// Removes all registered sinks.
+    pub fn clear_sinks(&mut self) {
+        self.sinks().clear();
+    }
+
+    /// Writes a log record to every sink that accepts its level.
+    ///
+    /// In asynchronous mode the record is only queued for the background thread.
+    /// The record is skipped if it is rate limited or a repeat of the previous record.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The record to be written.
     ///
     /// # Errors
     ///
-    /// If there is an error writing to the target, an error message will be printed to `stderr`.
-    pub fn write(&mut self, log_level: LogLevel, message: &str) {
-        let color = log_level.to_ansi_color();
-        let reset_color = "\x1b[0m";
-        let entry = match log_level {
-            LogLevel::Error => format!("{}[ERROR]: {}{}\n", color, message, reset_color),
-            LogLevel::Warn => format!("{}[WARN] : {}{}\n", color, message, reset_color),
-            LogLevel::Info => format!("{}[INFO] : {}{}\n", color, message, reset_color),
-        };
-        if let Err(e) = self.target.write_all(entry.as_bytes()) {
+    /// If there is an error writing to a sink, an error message will be printed to `stderr`.
+    pub fn write(&mut self, record: &Record) {
+        if let Some(rate_limit) = &mut self.rate_limit {
+            let (summaries, allowed) = rate_limit.check(record);
+            for summary in &summaries {
+                self.write_deduplicated(summary);
+            }
+            if !allowed {
+                return;
+            }
+        }
+        self.write_deduplicated(record);
+    }
+
+    /// Collapses consecutive identical records into "last message repeated N times".
+    ///
+    /// Records are identical when their level, target and message are equal.
+    /// The summary is written when a different record arrives or the log is flushed.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - `true` to collapse repeated records.
+    pub fn set_deduplicate(&mut self, enabled: bool) {
+        if !enabled {
+            self.write_repeat_summary();
+        }
+        self.dedup = enabled.then(Deduplicator::default);
+    }
+
+    /// Limits every call site to at most `max_per_second` records per second.
+    ///
+    /// The number of suppressed records is reported once the call site logs again
+    /// in a later second, or when the log is flushed.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_per_second` - Records allowed per call site and second, `None` to disable.
+    pub fn set_rate_limit(&mut self, max_per_second: Option<u32>) {
+        self.write_rate_limit_summaries();
+        self.rate_limit = max_per_second.map(RateLimiter::new);
+    }
+
+    /// Flushes all sinks.
+    ///
+    /// Pending "suppressed" and "repeated" summaries are written first.
+    /// In asynchronous mode this blocks until every queued record has been written.
+    /// Called from the background thread itself (e.g. by a panic hook), it does nothing,
+    /// since that thread holds the sinks and would wait for itself.
+    ///
+    /// # Errors
+    ///
+    /// If a sink fails to flush, an error message will be printed to `stderr`.
+    pub fn flush(&mut self) {
+        self.write_rate_limit_summaries();
+        self.write_repeat_summary();
+        if async_log::on_worker_thread() {
+            return;
+        }
+        if let Some(worker) = &self.worker {
+            worker.wait_idle();
+        }
+        flush(&mut self.sinks());
+    }
+
+    /// Switches the logger to asynchronous mode.
+    ///
+    /// Records are pushed into a bounded queue and written by a dedicated thread.
+    /// A running background thread is shut down and replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Queue capacity and overflow policy.
+    pub fn set_async(&mut self, config: AsyncConfig) {
+        self.shutdown();
+        self.worker = Some(AsyncWorker::spawn(
+            self.sinks.clone(),
+            config,
+            self.dropped.clone(),
+        ));
+    }
+
+    /// Writes every queued record, stops the background thread and flushes all sinks.
+    ///
+    /// The logger keeps working in synchronous mode afterwards.
+    /// Like `flush`, it does nothing on the background thread itself.
+    pub fn shutdown(&mut self) {
+        if async_log::on_worker_thread() {
+            return;
+        }
+        self.write_rate_limit_summaries();
+        self.write_repeat_summary();
+        if let Some(worker) = self.worker.take() {
+            worker.shutdown();
+        }
+        flush(&mut self.sinks());
+    }
+
+    /// Returns the number of records dropped because the asynchronous queue was full
+    /// or its background thread had stopped.
+    pub fn dropped_records(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Locks the sinks, even if a sink panicked while they were locked.
+    fn sinks(&self) -> MutexGuard<'_, Vec<SinkEntry>> {
+        self.sinks.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write_deduplicated(&mut self, record: &Record) {
+        if let Some(dedup) = &mut self.dedup {
+            let (summary, allowed) = dedup.check(record);
+            if let Some(summary) = summary {
+                self.write_to_sinks(&summary);
+            }
+            if !allowed {
+                return;
+            }
+        }
+        self.write_to_sinks(record);
+    }
+
+    fn write_rate_limit_summaries(&mut self) {
+        let summaries = self
+            .rate_limit
+            .as_mut()
+            .map(RateLimiter::take_summaries)
+            .unwrap_or_default();
+        for summary in &summaries {
+            self.write_deduplicated(summary);
+        }
+    }
+
+    fn write_repeat_summary(&mut self) {
+        if let Some(summary) = self.dedup.as_mut().and_then(Deduplicator::take_summary) {
+            self.write_to_sinks(&summary);
+        }
+    }
+
+    fn write_to_sinks(&mut self, record: &Record) {
+        match &self.worker {
+            Some(worker) => worker.push(record.clone()),
+            None => dispatch(&mut self.sinks(), record),
+        }
+    }
+}
+
+impl Drop for Log {
+    fn drop(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            worker.shutdown();
+        }
+    }
+}
+
+/// Writes a record to every sink that accepts its level.
+pub(crate) fn dispatch(sinks: &mut [SinkEntry], record: &Record) {
+    for entry in sinks.iter_mut() {
+        if record.level < entry.min_level {
+            continue;
+        }
+        if let Err(e) = entry.sink.write(record) {
             eprintln!("Failed to write log : {}", e);
         }
     }
 }
+
+/// Flushes every sink.
+pub(crate) fn flush(sinks: &mut [SinkEntry]) {
+    for entry in sinks.iter_mut() {
+        if let Err(e) = entry.sink.flush() {
+            eprintln!("Failed to flush log : {}", e);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::log_controller::LogController;
+use crate::log_level::LogLevel;
+use crate::record::Record;
+use crate::sink::Sink;
+use std::cell::Cell;
+use std::fmt::Write;
+
+thread_local! {
+    /// Set while `LogForwarder` hands a record or a flush to the `log` backend, so that a
+    /// `LogBridge` installed as that backend does not send it back to logy, which would
+    /// lock the global logger a second time.
+    static FORWARDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Adapter that implements `log::Log` on top of `LogController`.
+///
+/// Once installed, `log::info!` and friends from any crate land in the sinks of the
+/// global logger and go through the global filter (the `log` target is used as target).
+///
+/// # Example
+///
+/// ```rust
+/// use logy::{LogBridge, LogController};
+///
+/// LogController::init_logger();
+/// LogBridge::install().expect("another log backend is already installed");
+/// log::info!("decoded {} tiles", 8);
+/// ```
+pub struct LogBridge;
+
+impl LogBridge {
+    /// Installs the bridge as the backend of the `log` facade.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a `log` backend has already been installed.
+    pub fn install() -> Result<(), log::SetLoggerError> {
+        log::set_logger(&LogBridge)?;
+        log::set_max_level(log::LevelFilter::Trace);
+        Ok(())
+    }
+}
+
+impl log::Log for LogBridge {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        !FORWARDING.get() && LogController::enabled(metadata.level().into(), metadata.target())
+    }
+
+    fn log(&self, record: &log::Record) {
+        if FORWARDING.get() {
+            return;
+        }
+        let Some(logger_enabled) =
+            LogController::__private_enabled(record.level().into(), record.target())
+        else {
+            return;
+        };
+        let mut entry = Record::new(
+            record.level().into(),
+            record.target(),
+            record.args().to_string(),
+        );
+        entry.file = record.file().map(str::to_string);
+        entry.line = record.line();
+        LogController::__private_log(&entry, logger_enabled);
+    }
+
+    fn flush(&self) {
+        if FORWARDING.get() {
+            return;
+        }
+        LogController::flush();
+    }
+}
+
+/// A sink that forwards records to the backend installed for the `log` facade.
+///
+/// Use it when the application already has a `log` backend (e.g. `env_logger`)
+/// and logy macros should end up there too. Fields are appended to the message as
+/// `key=value` pairs.
+#[derive(Default)]
+pub struct LogForwarder;
+
+impl LogForwarder {
+    /// Creates a new forwarder.
+    pub fn new() -> LogForwarder {
+        Self
+    }
+}
+
+impl Sink for LogForwarder {
+    fn write(&mut self, record: &Record) -> std::io::Result<()> {
+        let mut message = record.message.clone();
+        for (key, value) in &record.fields {
+            let _ = write!(message, " {}={}", key, value);
+        }
+        FORWARDING.set(true);
+        log::logger().log(
+            &log::Record::builder()
+                .level(record.level.into())
+                .target(&record.target)
+                .module_path(Some(&record.target))
+                .file(record.file.as_deref())
+                .line(record.line)
+                .args(format_args!("{}", message))
+                .build(),
+        );
+        FORWARDING.set(false);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        FORWARDING.set(true);
+        log::logger().flush();
+        FORWARDING.set(false);
+        Ok(())
+    }
+}
+
+impl From<log::Level> for LogLevel {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Error => LogLevel::Error,
+            log::Level::Warn => LogLevel::Warn,
+            log::Level::Info => LogLevel::Info,
+            log::Level::Debug => LogLevel::Debug,
+            log::Level::Trace => LogLevel::Trace,
+        }
+    }
+}
+
+impl From<LogLevel> for log::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => log::Level::Error,
+            LogLevel::Warn => log::Level::Warn,
+            LogLevel::Info => log::Level::Info,
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Trace => log::Level::Trace,
+        }
+    }
+}
@@ -1,5 +1,16 @@
+use crate::async_log::AsyncConfig;
+use crate::capture;
+use crate::filter::Filter;
 use crate::log::Log;
-use crate::LOGGER;
+use crate::log_level::LogLevel;
+use crate::panic_hook::{self, CrashReportConfig};
+use crate::record::Record;
+use crate::ring_buffer::RingBuffer;
+use crate::sink::{Sink, WriterSink};
+use crate::span::{self, SpanEvent};
+use crate::{FILTER, LOGGER, LOGGER_INSTALLED, MAX_LEVEL, RING_BUFFER, START};
+use std::path::Path;
+use std::sync::atomic::Ordering;
 use std::sync::{Arc, Mutex};
 
 /// A global logger controller that manages logger.
@@ -8,11 +19,22 @@ use std::sync::{Arc, Mutex};
 pub struct LogController;
 
 impl LogController {
-    /// Sets the global logger instance.
+    /// Sets the global logger instance with a single `stdout` sink.
+    ///
+    /// Lines are colored if `stdout` is a terminal, unless `NO_COLOR` or `FORCE_COLOR` say otherwise.
+    ///
+    /// If the `LOGY` environment variable is set, it is parsed with `Filter::parse`
+    /// and installed as the global filter. The sink accepts every level, so the filter
+    /// alone decides what is printed.
     pub fn init_logger() {
-        let logger = Arc::new(Mutex::new(Log::new(std::io::stdout())));
-        let mut global_logger = LOGGER.lock().unwrap();
-        *global_logger = Some(logger);
+        match Filter::from_env() {
+            Some(Ok(filter)) => Self::set_filter(filter),
+            Some(Err(e)) => eprintln!("Invalid LOGY filter : {}", e),
+            None => {}
+        }
+        let mut logger = Log::default();
+        logger.add_sink(WriterSink::stdout(), LogLevel::Trace);
+        Self::set_logger(Arc::new(Mutex::new(logger)));
     }
 
     /// Sets the global logger instance.
@@ -22,9 +44,11 @@ impl LogController {
     /// * `logger` - The logger instance wrapped in `Arc` and `Mutex`.
     ///
     /// This function sets the logger to be used globally across the application.
-    pub fn set_logger(logger: Arc<Mutex<Log<std::io::Stdout>>>) {
+    pub fn set_logger(logger: Arc<Mutex<Log>>) {
+        lazy_static::initialize(&START);
         let mut global_logger = LOGGER.lock().unwrap();
         *global_logger = Some(logger);
+        LOGGER_INSTALLED.store(true, Ordering::Release);
     }
 
     /// Retrieves the global logger instance if it has been set.
@@ -32,8 +56,236 @@ impl LogController {
     /// # Returns
     ///
     /// An `Option` containing the logger wrapped in `Arc` and `Mutex`, or `None` if the logger is not set.
-    pub fn get_logger() -> Option<Arc<Mutex<Log<std::io::Stdout>>>> {
+    pub fn get_logger() -> Option<Arc<Mutex<Log>>> {
         let global_logger = LOGGER.lock().unwrap();
         global_logger.clone()
     }
+
+    /// Adds a sink to the global logger. An empty logger is installed first if none is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - The sink that receives records.
+    /// * `min_level` - Records less severe than this level are not sent to the sink.
+    pub fn add_sink<S: Sink + 'static>(sink: S, min_level: LogLevel) {
+        Self::logger_or_default()
+            .lock()
+            .unwrap()
+            .add_sink(sink, min_level);
+    }
+
+    /// Flushes every sink of the global logger, if it has been set.
+    ///
+    /// In asynchronous mode this blocks until every queued record has been written.
+    pub fn flush() {
+        if let Some(logger) = Self::get_logger() {
+            logger.lock().unwrap().flush();
+        }
+    }
+
+    /// Switches the global logger to asynchronous mode. An empty logger is installed first if none is set.
+    ///
+    /// Records are pushed into a bounded queue and written by a dedicated thread,
+    /// so the calling thread never waits for IO (unless the queue is full and the
+    /// overflow policy is `OverflowPolicy::Block`).
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Queue capacity and overflow policy.
+    pub fn set_async(config: AsyncConfig) {
+        Self::logger_or_default().lock().unwrap().set_async(config);
+    }
+
+    /// Writes every queued record, stops the background thread and flushes all sinks.
+    ///
+    /// Call this before the process exits. The global logger keeps working in synchronous mode afterwards.
+    pub fn shutdown() {
+        if let Some(logger) = Self::get_logger() {
+            logger.lock().unwrap().shutdown();
+        }
+    }
+
+    /// Sets the global threshold and removes all per-target levels.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - Records less severe than this level are discarded before formatting.
+    pub fn set_level(level: LogLevel) {
+        Self::set_filter(Filter::new(level));
+    }
+
+    /// Replaces the global filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The filter that decides which records are enabled.
+    pub fn set_filter(filter: Filter) {
+        let mut global_filter = FILTER.write().unwrap();
+        MAX_LEVEL.store(filter.max_level() as usize, Ordering::Relaxed);
+        *global_filter = filter;
+    }
+
+    /// Returns a copy of the global filter.
+    pub fn filter() -> Filter {
+        FILTER.read().unwrap().clone()
+    }
+
+    /// Checks whether a record of the given level and target would be written.
+    ///
+    /// This is used by the macros before the message is formatted. Returns `false`
+    /// when no logger is set, unless a `LogCapture` is active on the current thread.
+    pub fn enabled(level: LogLevel, target: &str) -> bool {
+        capture::is_active() || Self::logger_enabled(level, target)
+    }
+
+    /// Writes a record to the global logger, if it has been set and its filter enables the record.
+    ///
+    /// The record is also stored in every `LogCapture` active on the current thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The record to be written.
+    pub fn log(record: &Record) {
+        Self::__private_log(record, Self::logger_enabled(record.level, &record.target));
+    }
+
+    /// Used by the macros, which check the filter once before formatting the message.
+    ///
+    /// Returns `None` if the record would be discarded, otherwise whether the global
+    /// logger enables it (a `LogCapture` may want the record even if it does not).
+    #[doc(hidden)]
+    pub fn __private_enabled(level: LogLevel, target: &str) -> Option<bool> {
+        let logger_enabled = Self::logger_enabled(level, target);
+        (logger_enabled || capture::is_active()).then_some(logger_enabled)
+    }
+
+    /// Used by the macros with the result of `__private_enabled`, so the filter is not checked twice.
+    #[doc(hidden)]
+    pub fn __private_log(record: &Record, logger_enabled: bool) {
+        capture::capture(record);
+        if !logger_enabled {
+            return;
+        }
+        if let Some(logger) = Self::get_logger() {
+            logger.lock().unwrap().write(record);
+        }
+    }
+
+    /// Enables or disables collapsing of consecutive identical records on the global logger.
+    /// An empty logger is installed first if none is set.
+    ///
+    /// See `Log::set_deduplicate`.
+    pub fn set_deduplicate(enabled: bool) {
+        Self::logger_or_default()
+            .lock()
+            .unwrap()
+            .set_deduplicate(enabled);
+    }
+
+    /// Limits every call site to at most `max_per_second` records per second on the global logger.
+    /// An empty logger is installed first if none is set.
+    ///
+    /// See `Log::set_rate_limit`.
+    pub fn set_rate_limit(max_per_second: Option<u32>) {
+        Self::logger_or_default()
+            .lock()
+            .unwrap()
+            .set_rate_limit(max_per_second);
+    }
+
+    /// Installs a panic hook that reports panics through the global logger.
+    ///
+    /// On panic a crash report with the message, location, backtrace and the last
+    /// `config.history` log records is written to `config.path`. Then the panic is logged
+    /// at `LogLevel::Error` and all sinks are flushed, unless the panic happened on the
+    /// background thread of the asynchronous mode. The previously installed hook still
+    /// runs afterwards. An empty logger is installed first if none is set.
+    ///
+    /// The history comes from the global ring buffer, which is enabled with
+    /// `config.history` records and `LogLevel::Trace` if it is not enabled yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Crash report path and history size.
+    pub fn install_panic_hook(config: CrashReportConfig) {
+        let history = Self::ring_buffer()
+            .unwrap_or_else(|| Self::enable_ring_buffer(config.history, LogLevel::Trace));
+        panic_hook::install(history, config);
+    }
+
+    /// Keeps the most recent records of the global logger in a ring buffer.
+    /// An empty logger is installed first if none is set.
+    ///
+    /// A previously enabled ring buffer stays registered as a sink but is no longer
+    /// returned by `LogController::ring_buffer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum number of records kept.
+    /// * `min_level` - Records less severe than this level are not kept.
+    ///
+    /// # Returns
+    ///
+    /// The ring buffer, which can be queried at any time.
+    pub fn enable_ring_buffer(capacity: usize, min_level: LogLevel) -> RingBuffer {
+        let ring_buffer = RingBuffer::new(capacity);
+        Self::add_sink(ring_buffer.clone(), min_level);
+        *RING_BUFFER.lock().unwrap() = Some(ring_buffer.clone());
+        ring_buffer
+    }
+
+    /// Returns the global ring buffer, if it has been enabled.
+    pub fn ring_buffer() -> Option<RingBuffer> {
+        RING_BUFFER.lock().unwrap().clone()
+    }
+
+    /// Starts recording `lspan!` spans on every thread, discarding previously recorded ones.
+    pub fn start_profiling() {
+        span::start();
+    }
+
+    /// Stops recording spans.
+    ///
+    /// # Returns
+    ///
+    /// Every span that ended while profiling was enabled.
+    pub fn stop_profiling() -> Vec<SpanEvent> {
+        span::stop()
+    }
+
+    /// Stops recording spans and writes them as a Chrome Trace Event / Perfetto compatible JSON file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the trace file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written.
+    pub fn write_chrome_trace<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
+        span::write_chrome_trace(path, &span::stop())
+    }
+
+    /// Returns the number of records the global logger dropped because its asynchronous queue was full.
+    pub fn dropped_records() -> u64 {
+        Self::get_logger().map_or(0, |logger| logger.lock().unwrap().dropped_records())
+    }
+
+    fn logger_enabled(level: LogLevel, target: &str) -> bool {
+        if (level as usize) < MAX_LEVEL.load(Ordering::Relaxed) {
+            return false;
+        }
+        LOGGER_INSTALLED.load(Ordering::Acquire) && FILTER.read().unwrap().enabled(level, target)
+    }
+
+    fn logger_or_default() -> Arc<Mutex<Log>> {
+        lazy_static::initialize(&START);
+        let logger = LOGGER
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| Arc::new(Mutex::new(Log::default())))
+            .clone();
+        LOGGER_INSTALLED.store(true, Ordering::Release);
+        logger
+    }
 }
@@ -1,15 +1,33 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
 /// Enum representing the log states.
-#[derive(Clone)]
+///
+/// Levels are ordered by severity, so `LogLevel::Error > LogLevel::Trace`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LogLevel {
     /// Error state, shows there is an error
-    Error,
+    Error = 4,
     /// Warning states, shows there is a warning situation
-    Warn,
+    Warn = 3,
     /// Information state, use for an information purposes
-    Info,
+    Info = 2,
+    /// Debug state, use for diagnostics that are too noisy for normal runs
+    Debug = 1,
+    /// Trace state, use for very detailed diagnostics such as per frame messages
+    Trace = 0,
 }
 
 impl LogLevel {
+    /// All levels from the most to the least severe.
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
     /// Converts a `LogLevel` to the corresponding ANSI color code.
     ///
     /// # Returns
@@ -19,11 +37,44 @@ impl LogLevel {
     /// - `Error`: Red
     /// - `Warn`: Yellow
     /// - `Info`: Blue
+    /// - `Debug`: Cyan
+    /// - `Trace`: Gray
     pub fn to_ansi_color(&self) -> &str {
         match self {
             LogLevel::Error => "\x1b[91m", // Red
             LogLevel::Warn => "\x1b[93m",  // Yellow
             LogLevel::Info => "\x1b[94m",  // Blue
+            LogLevel::Debug => "\x1b[96m", // Cyan
+            LogLevel::Trace => "\x1b[90m", // Gray
         }
     }
+
+    /// Returns the upper case name of the level (e.g. `"WARN"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        }
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    /// Parses a level name case insensitively (`"error"`, `"Warn"`, `"TRACE"` ...).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LogLevel::ALL
+            .into_iter()
+            .find(|level| level.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("Unknown log level: {}", s))
+    }
 }
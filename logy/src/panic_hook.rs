@@ -0,0 +1,120 @@
+use crate::formatter::{Formatter, TextFormatter};
+use crate::log::Log;
+use crate::log_level::LogLevel;
+use crate::record::Record;
+use crate::ring_buffer::RingBuffer;
+use crate::time::{format_rfc3339, Timestamp};
+use crate::LOGGER;
+use std::backtrace::Backtrace;
+use std::fs::File;
+use std::io::Write;
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+use std::sync::TryLockError;
+use std::time::SystemTime;
+
+/// Configuration of the crash report written by the panic hook.
+#[derive(Clone, Debug)]
+pub struct CrashReportConfig {
+    /// Path of the crash report file. An existing file is overwritten.
+    pub path: PathBuf,
+    /// Number of most recent log records included in the report.
+    pub history: usize,
+}
+
+impl CrashReportConfig {
+    /// Creates a new configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the crash report file.
+    /// * `history` - Number of most recent log records included in the report.
+    pub fn new<P: Into<PathBuf>>(path: P, history: usize) -> CrashReportConfig {
+        Self {
+            path: path.into(),
+            history,
+        }
+    }
+}
+
+/// Installs a panic hook that writes a crash report, logs the panic and flushes the sinks.
+///
+/// The report is written first, so a logger that cannot be flushed (e.g. because the panic
+/// happened inside a sink) does not prevent it. The previous hook is still called afterwards,
+/// so the default message on `stderr` is kept.
+pub(crate) fn install(history: RingBuffer, config: CrashReportConfig) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let record = panic_record(info);
+        let recent = history.recent(config.history);
+        if let Err(e) = write_report(&config, &record, &recent) {
+            eprintln!("Failed to write crash report : {}", e);
+        }
+        with_logger(|logger| {
+            logger.write(&record);
+            logger.flush();
+        });
+        previous(info);
+    }));
+}
+
+fn panic_record(info: &PanicHookInfo) -> Record {
+    let payload = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string());
+    let location = info
+        .location()
+        .map_or_else(|| "unknown".to_string(), |l| l.to_string());
+    let thread = std::thread::current()
+        .name()
+        .unwrap_or("<unnamed>")
+        .to_string();
+    Record::new(
+        LogLevel::Error,
+        "panic",
+        format!("thread '{}' panicked at {}: {}", thread, location, payload),
+    )
+    .with_field("location", location)
+    .with_field("thread", thread)
+    .with_field("backtrace", Backtrace::force_capture().to_string())
+}
+
+/// Runs `f` with the global logger without blocking, the panic may come from inside the logger.
+fn with_logger(f: impl FnOnce(&mut Log)) {
+    let logger = match LOGGER.try_lock() {
+        Ok(logger) => logger.clone(),
+        Err(TryLockError::Poisoned(e)) => e.into_inner().clone(),
+        Err(TryLockError::WouldBlock) => None,
+    };
+    let Some(logger) = logger else {
+        return;
+    };
+    match logger.try_lock() {
+        Ok(mut log) => f(&mut log),
+        Err(TryLockError::Poisoned(e)) => f(&mut e.into_inner()),
+        Err(TryLockError::WouldBlock) => {}
+    };
+}
+
+fn write_report(
+    config: &CrashReportConfig,
+    record: &Record,
+    history: &[Record],
+) -> std::io::Result<()> {
+    let formatter = TextFormatter::plain().with_timestamp(Timestamp::Both);
+    let mut file = File::create(&config.path)?;
+    writeln!(file, "Crash report")?;
+    writeln!(file, "time: {}", format_rfc3339(SystemTime::now()))?;
+    writeln!(file, "{}", record.message)?;
+    if let Some(backtrace) = record.field("backtrace") {
+        writeln!(file, "\nbacktrace:\n{}", backtrace)?;
+    }
+    writeln!(file, "\nlast {} log records:", history.len())?;
+    for entry in history {
+        file.write_all(formatter.format(entry).as_bytes())?;
+    }
+    file.flush()
+}
@@ -0,0 +1,97 @@
+use crate::context::LogContext;
+use crate::log_level::LogLevel;
+use crate::time::format_rfc3339;
+use crate::value::Value;
+use crate::START;
+use std::time::{Duration, SystemTime};
+
+/// A single log entry passed from the macros to the logger and its sinks.
+#[derive(Clone, Debug)]
+pub struct Record {
+    /// Severity of the record.
+    pub level: LogLevel,
+    /// Origin of the record, usually the module path of the call site.
+    pub target: String,
+    /// The log message.
+    pub message: String,
+    /// Source file of the call site, if known.
+    pub file: Option<String>,
+    /// Source line of the call site, if known.
+    pub line: Option<u32>,
+    /// Structured key-value fields (frame number, entity id, asset path ...).
+    pub fields: Vec<(String, Value)>,
+    /// Wall clock time the record was created at.
+    pub time: SystemTime,
+    /// Monotonic time elapsed since the logger started when the record was created.
+    pub elapsed: Duration,
+}
+
+impl Record {
+    /// Creates a new record.
+    ///
+    /// The record inherits the fields of the thread's `LogContext`.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The level of the record.
+    /// * `target` - The origin of the record (e.g. `buji::core`).
+    /// * `message` - The log message.
+    ///
+    /// # Returns
+    ///
+    /// A new instance of the `Record`.
+    pub fn new(level: LogLevel, target: &str, message: impl Into<String>) -> Record {
+        Self {
+            level,
+            target: target.to_string(),
+            message: message.into(),
+            file: None,
+            line: None,
+            fields: LogContext::fields(),
+            time: SystemTime::now(),
+            elapsed: START.elapsed(),
+        }
+    }
+
+    /// Sets the source location of the record.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - Source file of the call site, usually `file!()`.
+    /// * `line` - Source line of the call site, usually `line!()`.
+    pub fn with_location(mut self, file: &str, line: u32) -> Record {
+        self.file = Some(file.to_string());
+        self.line = Some(line);
+        self
+    }
+
+    /// Attaches a key-value field to the record, replacing a field with the same key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Name of the field.
+    /// * `value` - Anything convertible to a field `Value`.
+    pub fn with_field(mut self, key: &str, value: impl Into<Value>) -> Record {
+        let value = value.into();
+        match self.fields.iter_mut().find(|(k, _)| k == key) {
+            Some(field) => field.1 = value,
+            None => self.fields.push((key.to_string(), value)),
+        }
+        self
+    }
+
+    /// Returns the value of the first field with the given key.
+    pub fn field(&self, key: &str) -> Option<&Value> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Returns the wall clock time of the record in RFC 3339 format.
+    pub fn rfc3339(&self) -> String {
+        format_rfc3339(self.time)
+    }
+
+    /// Returns the milliseconds elapsed since the logger started.
+    pub fn elapsed_millis(&self) -> u128 {
+        self.elapsed.as_millis()
+    }
+}
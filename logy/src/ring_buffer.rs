@@ -0,0 +1,157 @@
+use crate::record::Record;
+use crate::sink::Sink;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A fixed size buffer of the most recent records that can be queried at runtime.
+///
+/// Use it to show recent engine messages in an in-game console or debug overlay.
+/// Clones share the same buffer, so one clone can be registered as a sink while
+/// another is kept for queries. Writers only lock the single slot they overwrite.
+///
+/// # Example
+///
+/// ```rust
+/// use logy::{lwarn, LogController, LogLevel};
+///
+/// let recent = LogController::enable_ring_buffer(64, LogLevel::Info);
+/// lwarn!("Tried to load an invalid texture");
+///
+/// let (records, next) = recent.since(0);
+/// assert_eq!(records[0].message, "Tried to load an invalid texture");
+/// assert_eq!(next, 1);
+/// ```
+#[derive(Clone)]
+pub struct RingBuffer {
+    inner: Arc<Inner>,
+}
+
+/// A record together with its sequence number.
+type Slot = Option<(u64, Record)>;
+
+struct Inner {
+    slots: Box<[Mutex<Slot>]>,
+    next: AtomicU64,
+    /// Sequence number of the first record pushed after the last `clear`.
+    cleared: AtomicU64,
+}
+
+impl RingBuffer {
+    /// Creates an empty buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum number of records kept (at least 1).
+    pub fn new(capacity: usize) -> RingBuffer {
+        let slots = (0..capacity.max(1)).map(|_| Mutex::new(None)).collect();
+        Self {
+            inner: Arc::new(Inner {
+                slots,
+                next: AtomicU64::new(0),
+                cleared: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Returns the maximum number of records kept.
+    pub fn capacity(&self) -> usize {
+        self.inner.slots.len()
+    }
+
+    /// Returns the number of records currently kept.
+    pub fn len(&self) -> usize {
+        let next = self.inner.next.load(Ordering::Acquire);
+        (next - self.oldest(next)) as usize
+    }
+
+    /// Returns `true` if no record has been pushed since the buffer was created or cleared.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a record, overwriting the oldest one if the buffer is full.
+    pub fn push(&self, record: Record) {
+        let sequence = self.inner.next.fetch_add(1, Ordering::AcqRel);
+        let mut slot = self.slot(sequence);
+        if slot.as_ref().is_none_or(|(stored, _)| *stored < sequence) {
+            *slot = Some((sequence, record));
+        }
+    }
+
+    /// Returns up to `count` most recent records, oldest first.
+    pub fn recent(&self, count: usize) -> Vec<Record> {
+        let next = self.inner.next.load(Ordering::Acquire);
+        let first = next
+            .saturating_sub(count.min(self.capacity()) as u64)
+            .max(self.oldest(next));
+        self.collect(first, next)
+    }
+
+    /// Returns every kept record, oldest first.
+    pub fn snapshot(&self) -> Vec<Record> {
+        self.recent(self.capacity())
+    }
+
+    /// Returns the kept records pushed since a sequence number, oldest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `sequence` - The number returned by the previous call, `0` for the first call.
+    ///
+    /// # Returns
+    ///
+    /// The new records and the sequence number to pass to the next call. Records that
+    /// were overwritten in the meantime are skipped.
+    pub fn since(&self, sequence: u64) -> (Vec<Record>, u64) {
+        let next = self.inner.next.load(Ordering::Acquire);
+        let first = sequence.max(self.oldest(next));
+        (self.collect(first, next), next)
+    }
+
+    /// Removes every kept record.
+    ///
+    /// Sequence numbers keep counting, so readers using `since` only get the records
+    /// pushed after the clear.
+    pub fn clear(&self) {
+        let cleared = self.inner.next.load(Ordering::Acquire);
+        self.inner.cleared.fetch_max(cleared, Ordering::AcqRel);
+        for slot in self.inner.slots.iter() {
+            let mut slot = slot.lock().unwrap_or_else(PoisonError::into_inner);
+            // Keep records pushed concurrently after the clear.
+            if slot.as_ref().is_some_and(|(stored, _)| *stored < cleared) {
+                *slot = None;
+            }
+        }
+    }
+
+    /// Returns the sequence number of the oldest record that is still kept.
+    fn oldest(&self, next: u64) -> u64 {
+        let cleared = self.inner.cleared.load(Ordering::Acquire);
+        next.saturating_sub(self.capacity() as u64)
+            .max(cleared)
+            .min(next)
+    }
+
+    fn collect(&self, first: u64, next: u64) -> Vec<Record> {
+        (first..next)
+            .filter_map(|sequence| match &*self.slot(sequence) {
+                Some((stored, record)) if *stored == sequence => Some(record.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn slot(&self, sequence: u64) -> MutexGuard<'_, Slot> {
+        let index = (sequence % self.capacity() as u64) as usize;
+        self.inner.slots[index]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Sink for RingBuffer {
+    fn write(&mut self, record: &Record) -> std::io::Result<()> {
+        self.push(record.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use crate::time::days_since_epoch;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A log file that rotates when it grows over a byte limit or when the (UTC) day changes.
+///
+/// The active file keeps its name (e.g. `game.log`). Rotated files are renamed to
+/// `game.log.1` (newest) up to `game.log.N` (oldest), older ones are deleted.
+/// With the `gzip` feature rotated files can be compressed to `game.log.1.gz`.
+///
+/// Writes are buffered, use `flush` (or `LogController::flush`) before the process exits.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use logy::{LogController, LogLevel, RotatingFile, WriterSink};
+///
+/// let file = RotatingFile::new("game.log")?
+///     .max_bytes(10 * 1024 * 1024)
+///     .daily(true)
+///     .keep(7);
+/// LogController::add_sink(WriterSink::rotating(file), LogLevel::Debug);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct RotatingFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    size: u64,
+    day: u64,
+    max_bytes: Option<u64>,
+    daily: bool,
+    keep: usize,
+    compress: bool,
+}
+
+impl RotatingFile {
+    /// Opens (or creates) the active log file in append mode.
+    ///
+    /// By default the file never rotates and the last 5 rotated files are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the active log file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened.
+    pub fn new<P: AsRef<Path>>(path: P) -> std::io::Result<RotatingFile> {
+        let path = path.as_ref().to_path_buf();
+        let file = Self::open(&path)?;
+        let metadata = file.metadata()?;
+        let modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            size: metadata.len(),
+            day: days_since_epoch(modified),
+            max_bytes: None,
+            daily: false,
+            keep: 5,
+            compress: false,
+        })
+    }
+
+    /// Rotates the file before a write would make it larger than `max_bytes`.
+    pub fn max_bytes(mut self, max_bytes: u64) -> RotatingFile {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Rotates the file on the first write after the UTC day has changed.
+    pub fn daily(mut self, daily: bool) -> RotatingFile {
+        self.daily = daily;
+        self
+    }
+
+    /// Sets how many rotated files are kept. `0` deletes the file on rotation.
+    pub fn keep(mut self, keep: usize) -> RotatingFile {
+        self.keep = keep;
+        self
+    }
+
+    /// Compresses rotated files with gzip.
+    #[cfg(feature = "gzip")]
+    pub fn compress(mut self, compress: bool) -> RotatingFile {
+        self.compress = compress;
+        self
+    }
+
+    /// Returns the path of the active log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Closes the active file, shifts the rotated files and opens a new empty file.
+    ///
+    /// An active file that was deleted or moved away counts as rotated already. The active
+    /// file is reopened even if the rotation fails, and the size limit starts over, so a
+    /// failing rotation is not retried on every write.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be renamed, compressed or opened.
+    pub fn rotate(&mut self) -> std::io::Result<()> {
+        let flushed = self.writer.flush();
+        let rotated = self.rotate_files();
+        self.writer = BufWriter::new(Self::open(&self.path)?);
+        self.size = 0;
+        self.day = days_since_epoch(SystemTime::now());
+        flushed.and(rotated)
+    }
+
+    /// Moves the active file to `.1`. The history is only shifted once the active file
+    /// has been moved aside, so a failed rename does not delete a rotated file.
+    fn rotate_files(&self) -> std::io::Result<()> {
+        if self.keep == 0 {
+            return ignore_not_found(fs::remove_file(&self.path));
+        }
+        let pending = self.rotated_path(0, false);
+        match fs::rename(&self.path, &pending) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        }
+        let shifted = self.shift_history();
+        let rotated = self.rotated_path(1, false);
+        fs::rename(&pending, &rotated)?;
+        if self.compress {
+            Self::gzip(&rotated, &self.rotated_path(1, true))?;
+        }
+        shifted
+    }
+
+    /// Renames `.N` to `.N+1` and deletes the oldest file.
+    fn shift_history(&self) -> std::io::Result<()> {
+        for index in (1..=self.keep).rev() {
+            for compressed in [false, true] {
+                let from = self.rotated_path(index, compressed);
+                if !from.exists() {
+                    continue;
+                }
+                if index == self.keep {
+                    fs::remove_file(&from)?;
+                } else {
+                    fs::rename(&from, self.rotated_path(index + 1, compressed))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn should_rotate(&self, incoming: usize) -> bool {
+        let too_large = self
+            .max_bytes
+            .is_some_and(|max| self.size > 0 && self.size + incoming as u64 > max);
+        let new_day = self.daily && days_since_epoch(SystemTime::now()) != self.day;
+        too_large || new_day
+    }
+
+    fn rotated_path(&self, index: usize, compressed: bool) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        if compressed {
+            name.push(".gz");
+        }
+        PathBuf::from(name)
+    }
+
+    fn open(path: &Path) -> std::io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+
+    #[cfg(feature = "gzip")]
+    fn gzip(source: &Path, target: &Path) -> std::io::Result<()> {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let mut input = File::open(source)?;
+        let mut encoder = GzEncoder::new(File::create(target)?, Compression::default());
+        std::io::copy(&mut input, &mut encoder)?;
+        encoder.finish()?;
+        fs::remove_file(source)
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    fn gzip(_source: &Path, _target: &Path) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn ignore_not_found(result: std::io::Result<()>) -> std::io::Result<()> {
+    match result {
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.should_rotate(buf.len()) {
+            // Keep logging into whatever file is open, losing the record helps nobody.
+            if let Err(e) = self.rotate() {
+                eprintln!("Failed to rotate log file : {}", e);
+            }
+        }
+        let written = self.writer.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,147 @@
+use crate::formatter::{Formatter, TextFormatter};
+use crate::record::Record;
+use crate::rotating_file::RotatingFile;
+use crate::time::Timestamp;
+use std::fs::{File, OpenOptions};
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// A destination for log records.
+///
+/// Sinks are registered on a `Log` together with their own minimum level.
+pub trait Sink: Send {
+    /// Writes a single record to the sink.
+    ///
+    /// # Errors
+    ///
+    /// Returns the underlying IO error if the record could not be written.
+    fn write(&mut self, record: &Record) -> std::io::Result<()>;
+
+    /// Flushes any buffered output.
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A sink that formats records and writes them to any `Write` implementation.
+pub struct WriterSink {
+    target: Box<dyn Write + Send>,
+    formatter: Box<dyn Formatter>,
+}
+
+impl WriterSink {
+    /// Creates a new sink for the given target using the text formatter.
+    ///
+    /// The target is not known to be a terminal, so lines are only colored if `FORCE_COLOR` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target object where the log messages will be written to.
+    pub fn new<W: Write + Send + 'static>(target: W) -> WriterSink {
+        Self {
+            target: Box::new(target),
+            formatter: Box::new(TextFormatter::auto(false)),
+        }
+    }
+
+    /// Creates a sink that writes to `stdout`.
+    ///
+    /// Lines are colored if `stdout` is a terminal, unless `NO_COLOR` or `FORCE_COLOR` say otherwise.
+    pub fn stdout() -> WriterSink {
+        let is_terminal = std::io::stdout().is_terminal();
+        Self::new(std::io::stdout()).with_formatter(TextFormatter::auto(is_terminal))
+    }
+
+    /// Creates a sink that writes to `stderr`.
+    ///
+    /// Lines are colored if `stderr` is a terminal, unless `NO_COLOR` or `FORCE_COLOR` say otherwise.
+    pub fn stderr() -> WriterSink {
+        let is_terminal = std::io::stderr().is_terminal();
+        Self::new(std::io::stderr()).with_formatter(TextFormatter::auto(is_terminal))
+    }
+
+    /// Creates a sink that appends plain text lines to a file, creating it if needed.
+    /// Lines start with the wall clock and elapsed time.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the log file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened.
+    pub fn file<P: AsRef<Path>>(path: P) -> std::io::Result<WriterSink> {
+        let file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(file).with_formatter(TextFormatter::plain().with_timestamp(Timestamp::Both)))
+    }
+
+    /// Creates a sink that writes plain text lines to a rotating log file.
+    /// Lines start with the wall clock and elapsed time.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The configured rotating file.
+    pub fn rotating(file: RotatingFile) -> WriterSink {
+        Self::new(file).with_formatter(TextFormatter::plain().with_timestamp(Timestamp::Both))
+    }
+
+    /// Creates a sink that writes plain text lines into a shared in-memory buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The buffer to write to. Keep a clone of it to read the output.
+    pub fn memory(buffer: MemoryBuffer) -> WriterSink {
+        Self::new(buffer).with_formatter(TextFormatter::plain())
+    }
+
+    /// Replaces the formatter of the sink.
+    pub fn with_formatter<F: Formatter + 'static>(mut self, formatter: F) -> WriterSink {
+        self.formatter = Box::new(formatter);
+        self
+    }
+}
+
+impl Sink for WriterSink {
+    fn write(&mut self, record: &Record) -> std::io::Result<()> {
+        let entry = self.formatter.format(record);
+        self.target.write_all(entry.as_bytes())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.target.flush()
+    }
+}
+
+/// A cloneable, thread safe in-memory byte buffer that can be used as a log target.
+#[derive(Clone, Default)]
+pub struct MemoryBuffer {
+    bytes: Arc<Mutex<Vec<u8>>>,
+}
+
+impl MemoryBuffer {
+    /// Creates a new empty buffer.
+    pub fn new() -> MemoryBuffer {
+        Self::default()
+    }
+
+    /// Returns everything written to the buffer so far as a string.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.bytes.lock().unwrap()).into_owned()
+    }
+
+    /// Removes everything written to the buffer.
+    pub fn clear(&self) {
+        self.bytes.lock().unwrap().clear();
+    }
+}
+
+impl Write for MemoryBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.bytes.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
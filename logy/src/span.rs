@@ -0,0 +1,187 @@
+use crate::json;
+use crate::START;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static PROFILING: AtomicBool = AtomicBool::new(false);
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+static EVENTS: Mutex<Vec<SpanEvent>> = Mutex::new(Vec::new());
+static THREADS: Mutex<Vec<(u64, String)>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static THREAD_ID: u64 = register_thread();
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A finished span, as recorded while profiling is enabled.
+#[derive(Clone, Debug)]
+pub struct SpanEvent {
+    /// Name of the span (e.g. `"update"`).
+    pub name: Cow<'static, str>,
+    /// Module path of the code that opened the span.
+    pub target: &'static str,
+    /// Small sequential id of the thread that opened the span.
+    pub thread_id: u64,
+    /// Nesting level on its thread, `0` for outermost spans.
+    pub depth: usize,
+    /// Time the span was entered, relative to the logger start.
+    pub start: Duration,
+    /// Time spent between entering and leaving the span.
+    pub duration: Duration,
+}
+
+/// A guard that measures the time until it is dropped. Created with `lspan!`.
+///
+/// Spans only record something while profiling is enabled with
+/// `LogController::start_profiling`, otherwise they do nothing.
+#[must_use = "the span ends when the guard is dropped"]
+pub struct Span {
+    active: Option<ActiveSpan>,
+}
+
+struct ActiveSpan {
+    name: Cow<'static, str>,
+    target: &'static str,
+    depth: usize,
+    entered: Instant,
+}
+
+impl Span {
+    /// Enters a new span on the current thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the span.
+    /// * `target` - Module path of the caller, usually `module_path!()`.
+    pub fn enter(name: impl Into<Cow<'static, str>>, target: &'static str) -> Span {
+        if !Self::enabled() {
+            return Self::disabled();
+        }
+        let depth = DEPTH.get();
+        DEPTH.set(depth + 1);
+        Self {
+            active: Some(ActiveSpan {
+                name: name.into(),
+                target,
+                depth,
+                entered: Instant::now(),
+            }),
+        }
+    }
+
+    /// Returns a span that records nothing.
+    pub fn disabled() -> Span {
+        Self { active: None }
+    }
+
+    /// Returns `true` if spans are currently recorded.
+    pub fn enabled() -> bool {
+        PROFILING.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(active) = self.active.take() else {
+            return;
+        };
+        let duration = active.entered.elapsed();
+        DEPTH.set(active.depth);
+        let event = SpanEvent {
+            name: active.name,
+            target: active.target,
+            thread_id: THREAD_ID.with(|id| *id),
+            depth: active.depth,
+            start: active.entered.saturating_duration_since(*START),
+            duration,
+        };
+        if Self::enabled() {
+            EVENTS.lock().unwrap().push(event);
+        }
+    }
+}
+
+/// Starts recording spans, discarding previously recorded ones.
+pub(crate) fn start() {
+    lazy_static::initialize(&START);
+    EVENTS.lock().unwrap().clear();
+    PROFILING.store(true, Ordering::Relaxed);
+}
+
+/// Stops recording spans and returns the recorded events.
+pub(crate) fn stop() -> Vec<SpanEvent> {
+    PROFILING.store(false, Ordering::Relaxed);
+    std::mem::take(&mut EVENTS.lock().unwrap())
+}
+
+/// Writes span events as Chrome Trace Event JSON, which can be opened in
+/// `chrome://tracing` or the Perfetto UI.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be created or written.
+pub fn write_chrome_trace<P: AsRef<Path>>(path: P, events: &[SpanEvent]) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(chrome_trace_json(events).as_bytes())?;
+    file.flush()
+}
+
+/// Formats span events as a Chrome Trace Event JSON document.
+pub fn chrome_trace_json(events: &[SpanEvent]) -> String {
+    let pid = std::process::id();
+    let mut out = String::from("{\"displayTimeUnit\":\"ms\",\"traceEvents\":[");
+    let threads = THREADS.lock().unwrap().clone();
+    let mut first = true;
+    for (tid, name) in &threads {
+        if !events.iter().any(|event| event.thread_id == *tid) {
+            continue;
+        }
+        if !std::mem::take(&mut first) {
+            out.push(',');
+        }
+        let _ = write!(
+            out,
+            "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":{},\"tid\":{},\"args\":{{\"name\":",
+            pid, tid
+        );
+        json::push_str(&mut out, name);
+        out.push_str("}}");
+    }
+    for event in events {
+        if !std::mem::take(&mut first) {
+            out.push(',');
+        }
+        out.push_str("{\"name\":");
+        json::push_str(&mut out, &event.name);
+        out.push_str(",\"cat\":");
+        json::push_str(&mut out, event.target);
+        let _ = write!(
+            out,
+            ",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":{},\"tid\":{},\"args\":{{\"depth\":{}}}}}",
+            event.start.as_micros(),
+            event.duration.as_micros(),
+            pid,
+            event.thread_id,
+            event.depth
+        );
+    }
+    out.push_str("]}\n");
+    out
+}
+
+fn register_thread() -> u64 {
+    let id = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+    let thread = std::thread::current();
+    let name = thread
+        .name()
+        .map_or_else(|| format!("thread-{}", id), str::to_string);
+    THREADS.lock().unwrap().push((id, name));
+    id
+}
@@ -0,0 +1,376 @@
+use crate::log_level::LogLevel;
+use crate::record::Record;
+use crate::sink::Sink;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Largest UDP payload over IPv4, longer datagrams are truncated (RFC 5426, section 3.2).
+const MAX_DATAGRAM: usize = 65_507;
+
+/// How a `SyslogSink` reaches the syslog server.
+#[derive(Clone, Debug)]
+pub enum SyslogTransport {
+    /// One datagram per record, e.g. `"127.0.0.1:514"`.
+    Udp(String),
+    /// A stream with octet counting framing (RFC 6587), e.g. `"logs.local:601"`.
+    Tcp(String),
+    /// A Unix datagram socket, e.g. `"/dev/log"`.
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+enum Connection {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixDatagram),
+}
+
+/// A sink that sends records as RFC 5424 syslog messages over UDP, TCP or a Unix socket.
+///
+/// If the server cannot be reached, messages are kept in a bounded local fallback buffer
+/// (oldest dropped first) and sent once a reconnect succeeds. Reconnects are attempted
+/// at most once per `retry_interval`. A message that cannot be sent even on a new
+/// connection is dropped, so it does not hold back the messages behind it.
+///
+/// UDP and Unix socket messages are truncated to 65507 bytes.
+///
+/// Fields and the target are sent as structured data: `[logy@32473 target="buji::core" frame="42"]`.
+///
+/// # Example
+///
+/// ```rust
+/// use logy::{LogLevel, Record, Sink, SyslogSink, SyslogTransport};
+/// use std::net::UdpSocket;
+///
+/// // A local listener stands in for the syslog server.
+/// let listener = UdpSocket::bind("127.0.0.1:0")?;
+/// let address = listener.local_addr()?.to_string();
+///
+/// let mut sink = SyslogSink::new(SyslogTransport::Udp(address)).with_app_name("buji");
+/// sink.write(&Record::new(LogLevel::Warn, "buji::core", "Pre Exit..."))?;
+///
+/// let mut buffer = [0; 1024];
+/// let length = listener.recv(&mut buffer)?;
+/// let message = String::from_utf8_lossy(&buffer[..length]);
+/// assert!(message.starts_with("<12>1 "));
+/// assert!(message.ends_with("buji::core\"] Pre Exit..."));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct SyslogSink {
+    transport: SyslogTransport,
+    connection: Option<Connection>,
+    facility: u8,
+    hostname: String,
+    app_name: String,
+    fallback: VecDeque<Vec<u8>>,
+    fallback_capacity: usize,
+    dropped: u64,
+    retry_interval: Duration,
+    next_retry: Option<Instant>,
+    timeout: Duration,
+}
+
+impl SyslogSink {
+    /// Creates a new sink. The connection is opened on the first write.
+    ///
+    /// Defaults: facility `1` (user), the machine hostname, the executable name as
+    /// application name, a fallback buffer of 1000 messages, a 1 second retry interval
+    /// and a 1 second TCP connect and write timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - Address of the syslog server.
+    pub fn new(transport: SyslogTransport) -> SyslogSink {
+        Self {
+            transport,
+            connection: None,
+            facility: 1,
+            hostname: default_hostname(),
+            app_name: default_app_name(),
+            fallback: VecDeque::new(),
+            fallback_capacity: 1000,
+            dropped: 0,
+            retry_interval: Duration::from_secs(1),
+            next_retry: None,
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    /// Sets the syslog facility (0..=23, e.g. `16` for local0).
+    pub fn with_facility(mut self, facility: u8) -> SyslogSink {
+        self.facility = facility.min(23);
+        self
+    }
+
+    /// Sets the hostname written in every message.
+    pub fn with_hostname(mut self, hostname: &str) -> SyslogSink {
+        self.hostname = header_field(hostname, 255);
+        self
+    }
+
+    /// Sets the application name written in every message.
+    pub fn with_app_name(mut self, app_name: &str) -> SyslogSink {
+        self.app_name = header_field(app_name, 48);
+        self
+    }
+
+    /// Sets how many messages are buffered while the server is unreachable.
+    pub fn with_fallback_capacity(mut self, capacity: usize) -> SyslogSink {
+        self.fallback_capacity = capacity;
+        self
+    }
+
+    /// Sets the minimum time between two reconnect attempts.
+    pub fn with_retry_interval(mut self, interval: Duration) -> SyslogSink {
+        self.retry_interval = interval;
+        self
+    }
+
+    /// Sets how long connecting to or writing to a TCP server may block before the
+    /// message is buffered instead. A zero duration is ignored.
+    pub fn with_timeout(mut self, timeout: Duration) -> SyslogSink {
+        if !timeout.is_zero() {
+            self.timeout = timeout;
+        }
+        self
+    }
+
+    /// Returns the number of messages waiting in the fallback buffer.
+    pub fn pending(&self) -> usize {
+        self.fallback.len()
+    }
+
+    /// Returns the number of messages dropped because the fallback buffer was full
+    /// or they could not be sent on a new connection.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Formats a record as an RFC 5424 message without framing.
+    pub fn format(&self, record: &Record) -> String {
+        let severity = match record.level {
+            LogLevel::Error => 3,
+            LogLevel::Warn => 4,
+            LogLevel::Info => 6,
+            LogLevel::Debug | LogLevel::Trace => 7,
+        };
+        let mut data = format!("[logy@32473 target=\"{}\"", escape_param(&record.target));
+        for (key, value) in &record.fields {
+            let name: String = header_field(key, 32)
+                .chars()
+                .filter(|c| !matches!(c, '=' | ']' | '"'))
+                .collect();
+            data.push_str(&format!(
+                " {}=\"{}\"",
+                name,
+                escape_param(&value.to_string())
+            ));
+        }
+        data.push(']');
+        format!(
+            "<{}>1 {} {} {} {} - {} {}",
+            self.facility as u16 * 8 + severity,
+            record.rfc3339(),
+            self.hostname,
+            self.app_name,
+            std::process::id(),
+            data,
+            record.message
+        )
+    }
+
+    fn connect(&self) -> std::io::Result<Connection> {
+        match &self.transport {
+            SyslogTransport::Udp(address) => {
+                let socket = UdpSocket::bind(if address.starts_with('[') {
+                    "[::]:0"
+                } else {
+                    "0.0.0.0:0"
+                })?;
+                socket.connect(address)?;
+                Ok(Connection::Udp(socket))
+            }
+            SyslogTransport::Tcp(address) => {
+                let stream = self.connect_tcp(address)?;
+                stream.set_nodelay(true)?;
+                stream.set_write_timeout(Some(self.timeout))?;
+                Ok(Connection::Tcp(stream))
+            }
+            #[cfg(unix)]
+            SyslogTransport::Unix(path) => {
+                let socket = UnixDatagram::unbound()?;
+                socket.connect(path)?;
+                Ok(Connection::Unix(socket))
+            }
+        }
+    }
+
+    /// Tries every resolved address in turn, each bounded by the timeout.
+    fn connect_tcp(&self, address: &str) -> std::io::Result<TcpStream> {
+        let mut last_error = None;
+        for address in address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, self.timeout) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} did not resolve to any address", address),
+            )
+        }))
+    }
+
+    fn send(connection: &mut Connection, message: &[u8]) -> std::io::Result<()> {
+        match connection {
+            Connection::Udp(socket) => socket.send(message).map(|_| ()),
+            Connection::Tcp(stream) => {
+                stream.write_all(format!("{} ", message.len()).as_bytes())?;
+                stream.write_all(message)
+            }
+            #[cfg(unix)]
+            Connection::Unix(socket) => socket.send(message).map(|_| ()),
+        }
+    }
+
+    /// Opens the connection if needed and sends the buffered messages.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the connection is open and the fallback buffer is empty.
+    fn drain(&mut self) -> bool {
+        let fresh = self.connection.is_none();
+        if fresh {
+            if self.next_retry.is_some_and(|at| Instant::now() < at) {
+                return false;
+            }
+            match self.connect() {
+                Ok(connection) => {
+                    self.connection = Some(connection);
+                    self.next_retry = None;
+                }
+                Err(_) => {
+                    self.next_retry = Some(Instant::now() + self.retry_interval);
+                    return false;
+                }
+            }
+        }
+        while let Some(message) = self.fallback.front() {
+            let connection = self.connection.as_mut().expect("connection is open");
+            if Self::send(connection, message).is_err() {
+                if fresh {
+                    // The connection was just opened, the message itself is the problem.
+                    self.fallback.pop_front();
+                    self.dropped += 1;
+                }
+                self.connection = None;
+                self.next_retry = Some(Instant::now() + self.retry_interval);
+                return false;
+            }
+            self.fallback.pop_front();
+        }
+        true
+    }
+
+    fn buffer(&mut self, message: Vec<u8>) {
+        if self.fallback_capacity == 0 {
+            self.dropped += 1;
+            return;
+        }
+        if self.fallback.len() >= self.fallback_capacity {
+            self.fallback.pop_front();
+            self.dropped += 1;
+        }
+        self.fallback.push_back(message);
+    }
+}
+
+impl Sink for SyslogSink {
+    /// Sends the record, or buffers it locally if the server is unreachable.
+    fn write(&mut self, record: &Record) -> std::io::Result<()> {
+        let mut message = self.format(record).into_bytes();
+        if !matches!(self.transport, SyslogTransport::Tcp(_)) {
+            truncate(&mut message, MAX_DATAGRAM);
+        }
+        if self.drain() {
+            let connection = self.connection.as_mut().expect("connection is open");
+            if Self::send(connection, &message).is_ok() {
+                return Ok(());
+            }
+            self.connection = None;
+            self.next_retry = Some(Instant::now() + self.retry_interval);
+        }
+        self.buffer(message);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.drain();
+        if let Some(Connection::Tcp(stream)) = &mut self.connection {
+            stream.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Shortens a UTF-8 message to at most `max_length` bytes without splitting a character.
+fn truncate(message: &mut Vec<u8>, max_length: usize) {
+    if message.len() <= max_length {
+        return;
+    }
+    let mut length = max_length;
+    while length > 0 && message[length] & 0xc0 == 0x80 {
+        length -= 1;
+    }
+    message.truncate(length);
+}
+
+/// Escapes a structured data parameter value (`"`, `\` and `]`).
+fn escape_param(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Restricts a header field to printable ASCII without spaces, `-` if empty.
+fn header_field(value: &str, max_length: usize) -> String {
+    let field: String = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max_length)
+        .collect();
+    if field.is_empty() {
+        "-".to_string()
+    } else {
+        field
+    }
+}
+
+fn default_hostname() -> String {
+    let hostname = std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .unwrap_or_default();
+    header_field(hostname.trim(), 255)
+}
+
+fn default_app_name() -> String {
+    let name = std::env::current_exe()
+        .ok()
+        .and_then(|path| path.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .unwrap_or_default();
+    header_field(&name, 48)
+}
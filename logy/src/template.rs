@@ -0,0 +1,210 @@
+use crate::formatter::Formatter;
+use crate::record::Record;
+use crate::theme::{Theme, RESET_COLOR};
+use std::fmt::Write;
+
+/// Formatter that renders records with a user defined template.
+///
+/// Supported placeholders:
+///
+/// * `{time}` - RFC 3339 wall clock time
+/// * `{elapsed}` - milliseconds since the logger started
+/// * `{level}` - level name (`ERROR`, `WARN` ...)
+/// * `{target}` - module path of the call site
+/// * `{msg}` - the message
+/// * `{file}`, `{line}` - source location, empty if unknown
+/// * `{fields}` - fields as space separated `key=value` pairs
+///
+/// A placeholder can be padded with `<` (left), `>` (right) or `^` (center) and a width,
+/// e.g. `{level:>5}`. Use `{{` and `}}` for literal braces.
+///
+/// # Example
+///
+/// ```rust
+/// use logy::{LogLevel, Record, TemplateFormatter, Formatter};
+///
+/// let formatter = TemplateFormatter::new("{level:>5} {target}: {msg}").unwrap();
+/// let record = Record::new(LogLevel::Warn, "buji::core", "Pre Exit...");
+/// assert_eq!(formatter.format(&record), " WARN buji::core: Pre Exit...\n");
+/// ```
+pub struct TemplateFormatter {
+    segments: Vec<Segment>,
+    theme: Option<Theme>,
+}
+
+enum Segment {
+    Text(String),
+    Field {
+        placeholder: Placeholder,
+        align: Align,
+        width: usize,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum Placeholder {
+    Time,
+    Elapsed,
+    Level,
+    Target,
+    Message,
+    File,
+    Line,
+    Fields,
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+impl TemplateFormatter {
+    /// Parses a template. The formatter writes no colors until `with_theme` is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The line template, without the trailing line break.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message for unknown placeholders, invalid padding or unbalanced braces.
+    pub fn new(template: &str) -> Result<TemplateFormatter, String> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut spec = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => spec.push(c),
+                            None => return Err(format!("Unclosed placeholder: {{{}", spec)),
+                        }
+                    }
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(Self::parse_placeholder(&spec)?);
+                }
+                '}' => return Err("Unmatched '}' in template".to_string()),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(Self {
+            segments,
+            theme: None,
+        })
+    }
+
+    /// Colors every line with the theme color of its level.
+    pub fn with_theme(mut self, theme: Theme) -> TemplateFormatter {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Uses the default theme if `colored` is `true`, removes the theme otherwise.
+    ///
+    /// Combine with `color_enabled` to honor `NO_COLOR`, `FORCE_COLOR` and terminal detection.
+    pub fn colored(mut self, colored: bool) -> TemplateFormatter {
+        self.theme = colored.then(Theme::default);
+        self
+    }
+
+    fn parse_placeholder(spec: &str) -> Result<Segment, String> {
+        let (name, padding) = spec.split_once(':').unwrap_or((spec, ""));
+        let placeholder = match name.trim() {
+            "time" => Placeholder::Time,
+            "elapsed" => Placeholder::Elapsed,
+            "level" => Placeholder::Level,
+            "target" => Placeholder::Target,
+            "msg" => Placeholder::Message,
+            "file" => Placeholder::File,
+            "line" => Placeholder::Line,
+            "fields" => Placeholder::Fields,
+            other => return Err(format!("Unknown placeholder: {{{}}}", other)),
+        };
+        let (align, width) = match padding.chars().next() {
+            None => (Align::Left, ""),
+            Some('<') => (Align::Left, &padding[1..]),
+            Some('>') => (Align::Right, &padding[1..]),
+            Some('^') => (Align::Center, &padding[1..]),
+            Some(_) => (Align::Left, padding),
+        };
+        let width = if width.is_empty() {
+            0
+        } else {
+            width
+                .parse()
+                .map_err(|_| format!("Invalid padding in placeholder: {{{}}}", spec))?
+        };
+        Ok(Segment::Field {
+            placeholder,
+            align,
+            width,
+        })
+    }
+
+    fn value(placeholder: Placeholder, record: &Record) -> String {
+        match placeholder {
+            Placeholder::Time => record.rfc3339(),
+            Placeholder::Elapsed => record.elapsed_millis().to_string(),
+            Placeholder::Level => record.level.as_str().to_string(),
+            Placeholder::Target => record.target.clone(),
+            Placeholder::Message => record.message.clone(),
+            Placeholder::File => record.file.clone().unwrap_or_default(),
+            Placeholder::Line => record.line.map(|l| l.to_string()).unwrap_or_default(),
+            Placeholder::Fields => record
+                .fields
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+impl Formatter for TemplateFormatter {
+    fn format(&self, record: &Record) -> String {
+        let mut out = String::new();
+        if let Some(theme) = &self.theme {
+            out.push_str(theme.color(record.level));
+        }
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => out.push_str(text),
+                Segment::Field {
+                    placeholder,
+                    align,
+                    width,
+                } => {
+                    let value = Self::value(*placeholder, record);
+                    let _ = match align {
+                        Align::Left => write!(out, "{:<width$}", value, width = width),
+                        Align::Right => write!(out, "{:>width$}", value, width = width),
+                        Align::Center => write!(out, "{:^width$}", value, width = width),
+                    };
+                }
+            }
+        }
+        if self.theme.is_some() {
+            out.push_str(RESET_COLOR);
+        }
+        out.push('\n');
+        out
+    }
+}
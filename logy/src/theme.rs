@@ -0,0 +1,66 @@
+use crate::log_level::LogLevel;
+
+/// ANSI escape code that resets the color.
+pub const RESET_COLOR: &str = "\x1b[0m";
+
+/// Color codes used for each log level by the text and template formatters.
+///
+/// # Example
+///
+/// ```rust
+/// use logy::{LogLevel, TextFormatter, Theme};
+///
+/// let theme = Theme::default().with_color(LogLevel::Info, "\x1b[92m");
+/// let formatter = TextFormatter::colored().with_theme(theme);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Theme {
+    colors: [String; 5],
+}
+
+impl Theme {
+    /// Replaces the escape code of a level.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The level to change.
+    /// * `code` - ANSI escape code written in front of lines of that level (e.g. `"\x1b[1;31m"`).
+    pub fn with_color(mut self, level: LogLevel, code: &str) -> Theme {
+        self.colors[level as usize] = code.to_string();
+        self
+    }
+
+    /// Returns the escape code of a level.
+    pub fn color(&self, level: LogLevel) -> &str {
+        &self.colors[level as usize]
+    }
+}
+
+impl Default for Theme {
+    /// The colors of `LogLevel::to_ansi_color`.
+    fn default() -> Self {
+        let mut colors: [String; 5] = Default::default();
+        for level in LogLevel::ALL {
+            colors[level as usize] = level.to_ansi_color().to_string();
+        }
+        Self { colors }
+    }
+}
+
+/// Decides whether colored output should be written.
+///
+/// `FORCE_COLOR` (set and not `0`) always enables colors, a non empty `NO_COLOR`
+/// disables them (see <https://no-color.org>), otherwise colors are used only for terminals.
+///
+/// # Arguments
+///
+/// * `is_terminal` - Whether the target is a terminal.
+pub fn color_enabled(is_terminal: bool) -> bool {
+    if std::env::var("FORCE_COLOR").is_ok_and(|v| v != "0") {
+        return true;
+    }
+    if std::env::var("NO_COLOR").is_ok_and(|v| !v.is_empty()) {
+        return false;
+    }
+    is_terminal
+}
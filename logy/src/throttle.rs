@@ -0,0 +1,268 @@
+use crate::log_level::LogLevel;
+use crate::record::Record;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Collapses consecutive identical records.
+///
+/// Records are identical when their level, target and message are equal, fields are ignored
+/// so that per frame context does not defeat the deduplication.
+#[derive(Default)]
+pub(crate) struct Deduplicator {
+    last: Option<Record>,
+    repeated: u64,
+}
+
+impl Deduplicator {
+    /// Decides what to write for a new record.
+    ///
+    /// # Returns
+    ///
+    /// An optional "repeated" summary of the previous record and whether the new record should be written.
+    pub(crate) fn check(&mut self, record: &Record) -> (Option<Record>, bool) {
+        if let Some(last) = &self.last {
+            if last.level == record.level
+                && last.target == record.target
+                && last.message == record.message
+            {
+                self.repeated += 1;
+                return (None, false);
+            }
+        }
+        let summary = self.take_summary();
+        self.last = Some(record.clone());
+        (summary, true)
+    }
+
+    /// Returns the "repeated" summary of the last record, if it was repeated.
+    pub(crate) fn take_summary(&mut self) -> Option<Record> {
+        if self.repeated == 0 {
+            return None;
+        }
+        let last = self.last.as_ref()?;
+        let summary = Record::new(
+            last.level,
+            &last.target,
+            format!("last message repeated {} times", self.repeated),
+        );
+        self.repeated = 0;
+        Some(summary)
+    }
+}
+
+struct Window {
+    started: Instant,
+    level: LogLevel,
+    count: u32,
+    suppressed: u64,
+}
+
+impl Window {
+    fn new(started: Instant, level: LogLevel) -> Window {
+        Window {
+            started,
+            level,
+            count: 0,
+            suppressed: 0,
+        }
+    }
+}
+
+/// Windows are this long, and dropped once they are over.
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Limits how many records a single call site can write per second.
+///
+/// The call site is the source file and line of the record, or its target and message
+/// if the location is unknown.
+pub(crate) struct RateLimiter {
+    max_per_second: u32,
+    windows: HashMap<(String, String, u32), Window>,
+    last_prune: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(max_per_second: u32) -> RateLimiter {
+        Self {
+            max_per_second,
+            windows: HashMap::new(),
+            last_prune: Instant::now(),
+        }
+    }
+
+    /// Decides whether a record may be written.
+    ///
+    /// # Returns
+    ///
+    /// The summaries of records suppressed in windows that are over and whether
+    /// the record should be written.
+    pub(crate) fn check(&mut self, record: &Record) -> (Vec<Record>, bool) {
+        self.check_at(record, Instant::now())
+    }
+
+    /// Returns a summary for every call site that has suppressed records, and resets their counts.
+    ///
+    /// Used when the log is flushed, so suppressed records are reported even if the call site never logs again.
+    pub(crate) fn take_summaries(&mut self) -> Vec<Record> {
+        let max_per_second = self.max_per_second;
+        self.windows
+            .iter_mut()
+            .filter_map(|(key, window)| summary(max_per_second, &key.0, window))
+            .collect()
+    }
+
+    fn check_at(&mut self, record: &Record, now: Instant) -> (Vec<Record>, bool) {
+        let mut summaries = Vec::new();
+        if now.duration_since(self.last_prune) >= WINDOW {
+            self.prune(now, &mut summaries);
+        }
+
+        let key = match (&record.file, record.line) {
+            (Some(file), Some(line)) => (record.target.clone(), file.clone(), line),
+            _ => (record.target.clone(), record.message.clone(), 0),
+        };
+        let window = self
+            .windows
+            .entry(key)
+            .or_insert_with(|| Window::new(now, record.level));
+        if now.duration_since(window.started) >= WINDOW {
+            summaries.extend(summary(self.max_per_second, &record.target, window));
+            *window = Window::new(now, record.level);
+        }
+        if window.count >= self.max_per_second {
+            window.suppressed += 1;
+            return (summaries, false);
+        }
+        window.count += 1;
+        (summaries, true)
+    }
+
+    /// Removes the windows that are over, so call sites keyed by message do not pile up.
+    fn prune(&mut self, now: Instant, summaries: &mut Vec<Record>) {
+        let max_per_second = self.max_per_second;
+        self.windows.retain(|key, window| {
+            if now.duration_since(window.started) < WINDOW {
+                return true;
+            }
+            summaries.extend(summary(max_per_second, &key.0, window));
+            false
+        });
+        self.last_prune = now;
+    }
+}
+
+/// Returns the "suppressed" summary of a window, if it suppressed records, and resets its count.
+fn summary(max_per_second: u32, target: &str, window: &mut Window) -> Option<Record> {
+    if window.suppressed == 0 {
+        return None;
+    }
+    let summary = Record::new(
+        window.level,
+        target,
+        format!(
+            "{} records suppressed by the rate limit of {} per second",
+            window.suppressed, max_per_second
+        ),
+    );
+    window.suppressed = 0;
+    Some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spam() -> Record {
+        Record::new(LogLevel::Warn, "buji::physics", "spam")
+    }
+
+    #[test]
+    fn rate_limiter_reports_suppressed_records_in_the_next_window() {
+        let mut limiter = RateLimiter::new(2);
+        let start = Instant::now();
+        let allowed: Vec<bool> = (0..5).map(|_| limiter.check_at(&spam(), start).1).collect();
+        assert_eq!(allowed, [true, true, false, false, false]);
+
+        let (summaries, allowed) = limiter.check_at(&spam(), start + WINDOW);
+        assert!(allowed);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].level, LogLevel::Warn);
+        assert_eq!(summaries[0].target, "buji::physics");
+        assert_eq!(
+            summaries[0].message,
+            "3 records suppressed by the rate limit of 2 per second"
+        );
+    }
+
+    #[test]
+    fn rate_limiter_summaries_are_taken_once() {
+        let mut limiter = RateLimiter::new(1);
+        let start = Instant::now();
+        limiter.check_at(&spam(), start);
+        limiter.check_at(&spam(), start);
+
+        let summaries = limiter.take_summaries();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(
+            summaries[0].message,
+            "1 records suppressed by the rate limit of 1 per second"
+        );
+        assert!(limiter.take_summaries().is_empty());
+        assert!(limiter.check_at(&spam(), start + WINDOW).0.is_empty());
+    }
+
+    #[test]
+    fn rate_limiter_prunes_windows_that_are_over() {
+        let mut limiter = RateLimiter::new(1);
+        let start = limiter.last_prune;
+        for index in 0..100 {
+            let record = Record::new(LogLevel::Info, "buji", format!("frame {}", index));
+            limiter.check_at(&record, start);
+            limiter.check_at(&record, start);
+        }
+        assert_eq!(limiter.windows.len(), 100);
+
+        let (summaries, allowed) = limiter.check_at(&spam(), start + WINDOW);
+        assert!(allowed);
+        assert_eq!(summaries.len(), 100);
+        assert_eq!(limiter.windows.len(), 1);
+    }
+
+    /// Returns whether the record is written and the message of the summary, if any.
+    fn dedup_check(dedup: &mut Deduplicator, record: &Record) -> (Option<String>, bool) {
+        let (summary, allowed) = dedup.check(record);
+        (summary.map(|summary| summary.message), allowed)
+    }
+
+    #[test]
+    fn deduplicator_collapses_consecutive_identical_records() {
+        let mut dedup = Deduplicator::default();
+        assert_eq!(dedup_check(&mut dedup, &spam()), (None, true));
+        assert_eq!(dedup_check(&mut dedup, &spam()), (None, false));
+        assert_eq!(dedup_check(&mut dedup, &spam()), (None, false));
+
+        let other = Record::new(LogLevel::Warn, "buji::physics", "other");
+        let (summary, allowed) = dedup.check(&other);
+        assert!(allowed);
+        let summary = summary.unwrap();
+        assert_eq!(summary.level, LogLevel::Warn);
+        assert_eq!(summary.target, "buji::physics");
+        assert_eq!(summary.message, "last message repeated 2 times");
+        assert!(dedup.take_summary().is_none());
+    }
+
+    #[test]
+    fn deduplicator_ignores_fields_but_not_level() {
+        let mut dedup = Deduplicator::default();
+        dedup.check(&spam());
+        let with_field = spam().with_field("frame", 2);
+        assert_eq!(dedup_check(&mut dedup, &with_field), (None, false));
+
+        let error = Record::new(LogLevel::Error, "buji::physics", "spam");
+        assert_eq!(
+            dedup_check(&mut dedup, &error),
+            (Some("last message repeated 1 times".to_string()), true)
+        );
+        assert!(dedup.take_summary().is_none());
+    }
+}
@@ -0,0 +1,79 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Which timestamps a formatter writes for each record.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Timestamp {
+    /// No time information
+    #[default]
+    None,
+    /// Wall clock time in RFC 3339 format (UTC), e.g. `2024-06-10T18:30:05.123Z`
+    WallClock,
+    /// Monotonic milliseconds elapsed since the logger started, e.g. `+1532ms`
+    Elapsed,
+    /// Both the wall clock time and the elapsed milliseconds
+    Both,
+}
+
+impl Timestamp {
+    /// Returns `true` if the wall clock time should be written.
+    pub fn wall_clock(&self) -> bool {
+        matches!(self, Timestamp::WallClock | Timestamp::Both)
+    }
+
+    /// Returns `true` if the elapsed time should be written.
+    pub fn elapsed(&self) -> bool {
+        matches!(self, Timestamp::Elapsed | Timestamp::Both)
+    }
+}
+
+/// Formats a system time as an RFC 3339 UTC timestamp with millisecond precision.
+///
+/// # Example
+///
+/// ```rust
+/// use logy::format_rfc3339;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let time = UNIX_EPOCH + Duration::from_millis(1_718_044_205_123);
+/// assert_eq!(format_rfc3339(time), "2024-06-10T18:30:05.123Z");
+/// ```
+pub fn format_rfc3339(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO);
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let secs_of_day = secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Returns the number of whole days since the Unix epoch (UTC) for a system time.
+pub(crate) fn days_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+        / 86_400
+}
+
+/// Converts days since the Unix epoch to a (year, month, day) civil date.
+///
+/// Based on Howard Hinnant's `civil_from_days` algorithm.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
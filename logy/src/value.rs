@@ -0,0 +1,68 @@
+use std::fmt::{Display, Formatter};
+
+/// Value of a structured key-value field attached to a record.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// Text value
+    Str(String),
+    /// Signed integer value
+    Int(i64),
+    /// Unsigned integer value
+    UInt(u64),
+    /// Floating point value
+    Float(f64),
+    /// Boolean value
+    Bool(bool),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Str(v) => f.write_str(v),
+            Value::Int(v) => write!(f, "{}", v),
+            Value::UInt(v) => write!(f, "{}", v),
+            Value::Float(v) => write!(f, "{}", v),
+            Value::Bool(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+impl From<&String> for Value {
+    fn from(value: &String) -> Self {
+        Value::Str(value.clone())
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+macro_rules! impl_from_number {
+    ($variant:ident, $target:ty, $($source:ty),+) => {
+        $(
+            impl From<$source> for Value {
+                fn from(value: $source) -> Self {
+                    Value::$variant(value as $target)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_number!(Int, i64, i8, i16, i32, i64, isize);
+impl_from_number!(UInt, u64, u8, u16, u32, u64, usize);
+impl_from_number!(Float, f64, f32, f64);
@@ -0,0 +1,185 @@
+use logy::{AsyncConfig, Log, LogLevel, MemoryBuffer, OverflowPolicy, Record, Sink, WriterSink};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A sink that holds the background thread inside `write` while the gate is locked,
+/// so the test can fill the queue deterministically.
+struct GateSink {
+    gate: Arc<Mutex<()>>,
+    entered: Sender<()>,
+}
+
+impl Sink for GateSink {
+    fn write(&mut self, _record: &Record) -> std::io::Result<()> {
+        let _ = self.entered.send(());
+        drop(self.gate.lock().unwrap());
+        Ok(())
+    }
+}
+
+/// A sink that panics on the message `boom`.
+struct PanicSink;
+
+impl Sink for PanicSink {
+    fn write(&mut self, record: &Record) -> std::io::Result<()> {
+        assert_ne!(record.message, "boom", "sink panicked");
+        Ok(())
+    }
+}
+
+struct Fixture {
+    log: Arc<Mutex<Log>>,
+    buffer: MemoryBuffer,
+    gate: Arc<Mutex<()>>,
+    entered: Receiver<()>,
+}
+
+/// Creates an asynchronous log with a queue of 2 records.
+fn fixture(overflow: OverflowPolicy) -> Fixture {
+    let buffer = MemoryBuffer::new();
+    let gate = Arc::new(Mutex::new(()));
+    let (sender, entered) = mpsc::channel();
+    let mut log = Log::default();
+    log.add_sink(
+        GateSink {
+            gate: gate.clone(),
+            entered: sender,
+        },
+        LogLevel::Trace,
+    );
+    log.add_sink(WriterSink::memory(buffer.clone()), LogLevel::Trace);
+    log.set_async(AsyncConfig::new(2, overflow));
+    Fixture {
+        log: Arc::new(Mutex::new(log)),
+        buffer,
+        gate,
+        entered,
+    }
+}
+
+fn write(log: &Mutex<Log>, message: &str) {
+    log.lock()
+        .unwrap()
+        .write(&Record::new(LogLevel::Info, "buji", message));
+}
+
+impl Fixture {
+    /// Holds the background thread on `first` and queues `a` and `b`, so the queue is full.
+    fn fill(&self) -> MutexGuard<'_, ()> {
+        let closed = self.gate.lock().unwrap();
+        write(&self.log, "first");
+        self.entered.recv_timeout(TIMEOUT).unwrap();
+        write(&self.log, "a");
+        write(&self.log, "b");
+        closed
+    }
+
+    fn lines(&self) -> Vec<String> {
+        self.log.lock().unwrap().flush();
+        self.buffer
+            .contents()
+            .lines()
+            .map(|line| line.split(" : ").last().unwrap().to_string())
+            .collect()
+    }
+}
+
+#[test]
+fn block_waits_for_room_and_keeps_every_record() {
+    let fixture = fixture(OverflowPolicy::Block);
+    let closed = fixture.fill();
+
+    let (done, finished) = mpsc::channel();
+    let log = fixture.log.clone();
+    let writer = thread::spawn(move || {
+        // `write` takes the log lock, so only the queue itself must block here.
+        log.lock()
+            .unwrap()
+            .write(&Record::new(LogLevel::Info, "buji", "c"));
+        done.send(()).unwrap();
+    });
+    assert!(finished.recv_timeout(Duration::from_millis(200)).is_err());
+
+    drop(closed);
+    finished.recv_timeout(TIMEOUT).unwrap();
+    writer.join().unwrap();
+    assert_eq!(fixture.lines(), ["first", "a", "b", "c"]);
+    assert_eq!(fixture.log.lock().unwrap().dropped_records(), 0);
+}
+
+#[test]
+fn drop_newest_discards_the_new_record_and_reports_it() {
+    let fixture = fixture(OverflowPolicy::DropNewest);
+    let closed = fixture.fill();
+    write(&fixture.log, "c");
+    drop(closed);
+
+    assert_eq!(
+        fixture.lines(),
+        [
+            "first",
+            "1 log records dropped, the queue was full",
+            "a",
+            "b"
+        ]
+    );
+    assert_eq!(fixture.log.lock().unwrap().dropped_records(), 1);
+}
+
+#[test]
+fn drop_oldest_discards_the_oldest_queued_record_and_reports_it() {
+    let fixture = fixture(OverflowPolicy::DropOldest);
+    let closed = fixture.fill();
+    write(&fixture.log, "c");
+    write(&fixture.log, "d");
+    drop(closed);
+
+    assert_eq!(
+        fixture.lines(),
+        [
+            "first",
+            "2 log records dropped, the queue was full",
+            "c",
+            "d"
+        ]
+    );
+    assert_eq!(fixture.log.lock().unwrap().dropped_records(), 2);
+}
+
+#[test]
+fn dropped_records_survive_shutdown() {
+    let fixture = fixture(OverflowPolicy::DropNewest);
+    let closed = fixture.fill();
+    write(&fixture.log, "c");
+    drop(closed);
+
+    let mut log = fixture.log.lock().unwrap();
+    log.shutdown();
+    assert_eq!(log.dropped_records(), 1);
+}
+
+#[test]
+fn panicking_sink_does_not_hang_flush_or_block() {
+    let (done, finished) = mpsc::channel();
+    thread::spawn(move || {
+        let mut log = Log::default();
+        log.add_sink(PanicSink, LogLevel::Trace);
+        log.set_async(AsyncConfig::new(1, OverflowPolicy::Block));
+
+        log.write(&Record::new(LogLevel::Error, "buji", "boom"));
+        log.flush();
+        // The background thread is gone, so the full queue must not block either.
+        for _ in 0..3 {
+            log.write(&Record::new(LogLevel::Info, "buji", "after"));
+        }
+        log.flush();
+        done.send(log.dropped_records()).unwrap();
+    });
+
+    let dropped = finished.recv_timeout(TIMEOUT).expect("flush hung");
+    assert_eq!(dropped, 3);
+}
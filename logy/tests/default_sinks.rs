@@ -0,0 +1,61 @@
+use logy::{ldebug, LogController};
+use std::env;
+use std::process::Command;
+
+/// Set when the test binary runs itself as a child process with `LOGY` configured.
+const CHILD_ENV: &str = "LOGY_TEST_CHILD";
+
+/// Runs `test` in a child process with the given `LOGY` value and returns its `stdout`.
+fn run_child(test: &str, filter: &str) -> String {
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["--exact", test, "--nocapture"])
+        .env(CHILD_ENV, "1")
+        .env("LOGY", filter)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert!(output.status.success(), "{}", stdout);
+    stdout
+}
+
+/// Logs a debug record through the default logger when running as the child process.
+fn log_debug_in_child() -> bool {
+    if env::var_os(CHILD_ENV).is_none() {
+        return false;
+    }
+    LogController::init_logger();
+    ldebug!("decoded 8 tiles");
+    LogController::flush();
+    true
+}
+
+#[test]
+fn target_debug_filter_reaches_default_stdout_sink() {
+    if log_debug_in_child() {
+        return;
+    }
+    let stdout = run_child(
+        "target_debug_filter_reaches_default_stdout_sink",
+        "default_sinks=debug",
+    );
+    assert!(stdout.contains("decoded 8 tiles"), "{}", stdout);
+}
+
+#[test]
+fn global_debug_filter_reaches_default_stdout_sink() {
+    if log_debug_in_child() {
+        return;
+    }
+    let stdout = run_child("global_debug_filter_reaches_default_stdout_sink", "debug");
+    assert!(stdout.contains("decoded 8 tiles"), "{}", stdout);
+}
+
+#[test]
+fn default_filter_hides_debug_records() {
+    if log_debug_in_child() {
+        return;
+    }
+    let stdout = run_child("default_filter_hides_debug_records", "");
+    assert!(!stdout.contains("decoded 8 tiles"), "{}", stdout);
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "log")]
+
+use logy::{lwarn, LogBridge, LogController, LogForwarder, LogLevel, MemoryBuffer, WriterSink};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn forwarder_and_bridge_together_neither_loop_nor_deadlock() {
+    let buffer = MemoryBuffer::new();
+    LogController::add_sink(WriterSink::memory(buffer.clone()), LogLevel::Trace);
+    LogController::add_sink(LogForwarder::new(), LogLevel::Trace);
+    LogBridge::install().unwrap();
+
+    let (done, finished) = mpsc::channel();
+    thread::spawn(move || {
+        lwarn!("from logy");
+        log::warn!("from log");
+        LogController::flush();
+        log::logger().flush();
+        done.send(()).unwrap();
+    });
+    finished
+        .recv_timeout(Duration::from_secs(5))
+        .expect("logging through both adapters deadlocked");
+
+    let contents = buffer.contents();
+    assert_eq!(contents.matches("from logy").count(), 1, "{}", contents);
+    assert_eq!(contents.matches("from log\n").count(), 1, "{}", contents);
+}
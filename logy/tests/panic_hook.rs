@@ -0,0 +1,34 @@
+use logy::{linfo, CrashReportConfig, LogController, LogLevel, MemoryBuffer, WriterSink};
+use std::thread;
+
+#[test]
+fn panic_on_a_spawned_thread_writes_a_crash_report() {
+    let path = std::env::temp_dir().join(format!("logy-crash-{}.txt", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let buffer = MemoryBuffer::new();
+    LogController::add_sink(WriterSink::memory(buffer.clone()), LogLevel::Trace);
+    LogController::install_panic_hook(CrashReportConfig::new(&path, 10));
+
+    linfo!("loading level 3");
+    let result = thread::Builder::new()
+        .name("game".to_string())
+        .spawn(|| panic!("tile index out of range"))
+        .unwrap()
+        .join();
+    assert!(result.is_err());
+
+    let report = std::fs::read_to_string(&path).unwrap();
+    assert!(report.starts_with("Crash report\n"), "{}", report);
+    assert!(
+        report.contains("thread 'game' panicked at logy/tests/panic_hook.rs:"),
+        "{}",
+        report
+    );
+    assert!(report.contains("tile index out of range"), "{}", report);
+    assert!(report.contains("\nbacktrace:\n"), "{}", report);
+    assert!(report.contains("last 1 log records:"), "{}", report);
+    assert!(report.contains("loading level 3"), "{}", report);
+    assert!(buffer.contents().contains("tile index out of range"));
+
+    let _ = std::fs::remove_file(&path);
+}
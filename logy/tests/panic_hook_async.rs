@@ -0,0 +1,47 @@
+use logy::{
+    lerror, AsyncConfig, CrashReportConfig, LogController, LogLevel, OverflowPolicy, Record, Sink,
+};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A sink that panics on the message `boom`.
+struct PanicSink;
+
+impl Sink for PanicSink {
+    fn write(&mut self, record: &Record) -> std::io::Result<()> {
+        assert_ne!(record.message, "boom", "sink panicked");
+        Ok(())
+    }
+}
+
+#[test]
+fn panic_on_the_logy_thread_writes_a_report_and_shutdown_returns() {
+    let path = std::env::temp_dir().join(format!("logy-crash-async-{}.txt", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    LogController::set_async(AsyncConfig::new(8, OverflowPolicy::Block));
+    LogController::install_panic_hook(CrashReportConfig::new(&path, 10));
+    LogController::add_sink(PanicSink, LogLevel::Trace);
+
+    lerror!("boom");
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !path.exists() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    let (done, finished) = mpsc::channel();
+    thread::spawn(move || {
+        LogController::shutdown();
+        done.send(()).unwrap();
+    });
+    finished
+        .recv_timeout(Duration::from_secs(5))
+        .expect("shutdown hung");
+
+    let report = std::fs::read_to_string(&path).unwrap();
+    assert!(report.contains("thread 'logy' panicked at"), "{}", report);
+    assert!(report.contains("sink panicked"), "{}", report);
+    assert!(report.contains("boom"), "{}", report);
+
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,48 @@
+use logy::{LogLevel, Record, RingBuffer};
+
+fn push(buffer: &RingBuffer, message: &str) {
+    buffer.push(Record::new(LogLevel::Info, "buji", message));
+}
+
+fn messages(records: Vec<Record>) -> Vec<String> {
+    records.into_iter().map(|record| record.message).collect()
+}
+
+#[test]
+fn keeps_only_the_most_recent_records() {
+    let buffer = RingBuffer::new(2);
+    for message in ["a", "b", "c"] {
+        push(&buffer, message);
+    }
+    assert_eq!(buffer.len(), 2);
+    assert_eq!(messages(buffer.snapshot()), ["b", "c"]);
+    assert_eq!(messages(buffer.recent(1)), ["c"]);
+
+    let (records, next) = buffer.since(0);
+    assert_eq!(messages(records), ["b", "c"]);
+    assert_eq!(next, 3);
+}
+
+#[test]
+fn clear_resets_len_recent_and_since() {
+    let buffer = RingBuffer::new(4);
+    for message in ["a", "b", "c"] {
+        push(&buffer, message);
+    }
+    let (_, next) = buffer.since(0);
+    buffer.clear();
+
+    assert_eq!(buffer.len(), 0);
+    assert!(buffer.is_empty());
+    assert!(buffer.recent(4).is_empty());
+    let (records, since_next) = buffer.since(0);
+    assert!(records.is_empty());
+    assert_eq!(since_next, next);
+
+    push(&buffer, "d");
+    assert_eq!(buffer.len(), 1);
+    assert_eq!(messages(buffer.snapshot()), ["d"]);
+    let (records, next) = buffer.since(next);
+    assert_eq!(messages(records), ["d"]);
+    assert_eq!(next, 4);
+}
@@ -0,0 +1,145 @@
+use logy::RotatingFile;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A fresh directory per test, removed again when the test passes.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> TempDir {
+        let path = std::env::temp_dir().join(format!("logy-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+
+    fn read(&self, name: &str) -> String {
+        fs::read_to_string(self.file(name)).unwrap_or_default()
+    }
+
+    /// Names of the files in the directory, sorted.
+    fn files(&self) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(&self.0)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Writes a 20 byte line in one call, like `WriterSink` does for a record.
+fn line(file: &mut RotatingFile, index: usize) {
+    file.write_all(format!("line {:<14}\n", index).as_bytes())
+        .unwrap();
+    file.flush().unwrap();
+}
+
+fn sized(path: &Path) -> RotatingFile {
+    RotatingFile::new(path).unwrap().max_bytes(30).keep(3)
+}
+
+#[test]
+fn rotates_by_size_and_keeps_the_newest_files() {
+    let dir = TempDir::new("size");
+    let mut file = sized(&dir.file("g.log"));
+    for index in 1..=5 {
+        line(&mut file, index);
+    }
+
+    assert_eq!(dir.files(), ["g.log", "g.log.1", "g.log.2", "g.log.3"]);
+    assert!(dir.read("g.log").starts_with("line 5 "));
+    assert!(dir.read("g.log.1").starts_with("line 4 "));
+    assert!(dir.read("g.log.2").starts_with("line 3 "));
+    assert!(dir.read("g.log.3").starts_with("line 2 "));
+}
+
+#[test]
+fn keep_zero_deletes_the_rotated_file() {
+    let dir = TempDir::new("keep-zero");
+    let mut file = RotatingFile::new(dir.file("g.log"))
+        .unwrap()
+        .max_bytes(30)
+        .keep(0);
+    line(&mut file, 1);
+    line(&mut file, 2);
+
+    assert_eq!(dir.files(), ["g.log"]);
+    assert!(dir.read("g.log").starts_with("line 2 "));
+}
+
+#[test]
+fn deleted_active_file_is_recreated_without_losing_history() {
+    let dir = TempDir::new("deleted");
+    let mut file = sized(&dir.file("g.log"));
+    for index in 1..=4 {
+        line(&mut file, index);
+    }
+    fs::remove_file(dir.file("g.log")).unwrap();
+
+    for index in 5..=6 {
+        line(&mut file, index);
+    }
+
+    // Line 5 starts a new file without shifting, line 6 rotates normally.
+    assert_eq!(dir.files(), ["g.log", "g.log.1", "g.log.2", "g.log.3"]);
+    assert!(dir.read("g.log").starts_with("line 6 "));
+    assert!(dir.read("g.log.1").starts_with("line 5 "));
+    assert!(dir.read("g.log.2").starts_with("line 3 "));
+    assert!(dir.read("g.log.3").starts_with("line 2 "));
+}
+
+#[test]
+fn daily_rotates_a_file_from_an_earlier_day() {
+    let dir = TempDir::new("daily");
+    let path = dir.file("g.log");
+    fs::write(&path, "yesterday\n").unwrap();
+    let yesterday = SystemTime::now() - Duration::from_secs(2 * 86_400);
+    fs::File::options()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(yesterday)
+        .unwrap();
+
+    let mut file = RotatingFile::new(&path).unwrap().daily(true);
+    line(&mut file, 1);
+    line(&mut file, 2);
+
+    assert_eq!(dir.files(), ["g.log", "g.log.1"]);
+    assert_eq!(dir.read("g.log.1"), "yesterday\n");
+    assert!(dir.read("g.log").starts_with("line 1 "));
+    assert!(dir.read("g.log").contains("line 2 "));
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn compress_gzips_rotated_files() {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let dir = TempDir::new("gzip");
+    let mut file = sized(&dir.file("g.log")).compress(true);
+    for index in 1..=3 {
+        line(&mut file, index);
+    }
+
+    assert_eq!(dir.files(), ["g.log", "g.log.1.gz", "g.log.2.gz"]);
+    let mut decoded = String::new();
+    GzDecoder::new(fs::File::open(dir.file("g.log.2.gz")).unwrap())
+        .read_to_string(&mut decoded)
+        .unwrap();
+    assert!(decoded.starts_with("line 1 "));
+}
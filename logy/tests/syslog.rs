@@ -0,0 +1,148 @@
+use logy::{LogLevel, Record, Sink, SyslogSink, SyslogTransport};
+use std::io::{BufRead, BufReader, Read};
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::time::Duration;
+
+/// Reads one octet counted frame (`<length> <message>`) from the stream.
+fn read_frame(reader: &mut BufReader<TcpStream>) -> String {
+    let mut length = Vec::new();
+    reader.read_until(b' ', &mut length).unwrap();
+    let length: usize = String::from_utf8(length).unwrap().trim().parse().unwrap();
+    let mut message = vec![0; length];
+    reader.read_exact(&mut message).unwrap();
+    String::from_utf8(message).unwrap()
+}
+
+fn accept(listener: &TcpListener) -> BufReader<TcpStream> {
+    let (stream, _) = listener.accept().unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    BufReader::new(stream)
+}
+
+#[test]
+fn tcp_messages_use_octet_counting() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let mut sink = SyslogSink::new(SyslogTransport::Tcp(address)).with_app_name("buji");
+
+    sink.write(&Record::new(LogLevel::Error, "buji::core", "first"))
+        .unwrap();
+    sink.write(&Record::new(LogLevel::Info, "buji::core", "second message"))
+        .unwrap();
+    sink.flush().unwrap();
+
+    let mut reader = accept(&listener);
+    let first = read_frame(&mut reader);
+    let second = read_frame(&mut reader);
+    assert!(first.starts_with("<11>1 "), "{}", first);
+    assert!(first.ends_with("buji::core\"] first"), "{}", first);
+    assert!(second.starts_with("<14>1 "), "{}", second);
+    assert!(
+        second.ends_with("buji::core\"] second message"),
+        "{}",
+        second
+    );
+    assert_eq!(sink.pending(), 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_socket_receives_one_datagram_per_record() {
+    use std::os::unix::net::UnixDatagram;
+
+    let path = std::env::temp_dir().join(format!("logy-syslog-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixDatagram::bind(&path).unwrap();
+    listener
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    let mut sink = SyslogSink::new(SyslogTransport::Unix(path.clone())).with_facility(16);
+    sink.write(&Record::new(
+        LogLevel::Warn,
+        "buji::asset_server",
+        "missing tile",
+    ))
+    .unwrap();
+
+    let mut buffer = [0; 1024];
+    let length = listener.recv(&mut buffer).unwrap();
+    let message = String::from_utf8_lossy(&buffer[..length]);
+    assert!(message.starts_with("<132>1 "), "{}", message);
+    assert!(
+        message.ends_with("buji::asset_server\"] missing tile"),
+        "{}",
+        message
+    );
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn fallback_buffer_drains_once_the_listener_is_back() {
+    // Reserve a port, then close it so the first writes cannot connect.
+    let address = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    };
+    let mut sink = SyslogSink::new(SyslogTransport::Tcp(address.to_string()))
+        .with_fallback_capacity(2)
+        .with_retry_interval(Duration::ZERO);
+
+    for message in ["dropped", "kept 1", "kept 2"] {
+        sink.write(&Record::new(LogLevel::Info, "buji", message))
+            .unwrap();
+    }
+    assert_eq!(sink.pending(), 2);
+    assert_eq!(sink.dropped(), 1);
+
+    let listener = TcpListener::bind(address).unwrap();
+    sink.write(&Record::new(LogLevel::Info, "buji", "live"))
+        .unwrap();
+    sink.flush().unwrap();
+    assert_eq!(sink.pending(), 0);
+
+    let mut reader = accept(&listener);
+    for expected in ["kept 1", "kept 2", "live"] {
+        let message = read_frame(&mut reader);
+        assert!(message.ends_with(expected), "{}", message);
+    }
+}
+
+#[test]
+fn oversized_udp_message_is_truncated_and_does_not_block_the_rest() {
+    let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+    listener
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let mut sink = SyslogSink::new(SyslogTransport::Udp(address));
+
+    sink.write(&Record::new(LogLevel::Info, "buji", "é".repeat(35_000)))
+        .unwrap();
+    for index in 0..5 {
+        sink.write(&Record::new(
+            LogLevel::Info,
+            "buji",
+            format!("small {}", index),
+        ))
+        .unwrap();
+    }
+    assert_eq!(sink.pending(), 0);
+
+    let mut buffer = vec![0; 70_000];
+    let length = listener.recv(&mut buffer).unwrap();
+    assert!(length <= 65_507, "{}", length);
+    assert!(std::str::from_utf8(&buffer[..length]).is_ok());
+    for index in 0..5 {
+        let length = listener.recv(&mut buffer).unwrap();
+        let message = String::from_utf8_lossy(&buffer[..length]);
+        assert!(
+            message.ends_with(&format!("small {}", index)),
+            "{}",
+            message
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use logy::{Log, LogLevel, MemoryBuffer, Record, WriterSink};
+
+fn log() -> (Log, MemoryBuffer) {
+    let buffer = MemoryBuffer::new();
+    let mut log = Log::default();
+    log.add_sink(WriterSink::memory(buffer.clone()), LogLevel::Trace);
+    (log, buffer)
+}
+
+fn write(log: &mut Log, message: &str) {
+    log.write(&Record::new(LogLevel::Info, "buji", message));
+}
+
+fn lines(buffer: &MemoryBuffer) -> Vec<String> {
+    buffer
+        .contents()
+        .lines()
+        .map(|line| line.split(" : ").last().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn flush_reports_repeated_records() {
+    let (mut log, buffer) = log();
+    log.set_deduplicate(true);
+    for _ in 0..3 {
+        write(&mut log, "spam");
+    }
+    write(&mut log, "other");
+    write(&mut log, "other");
+    log.flush();
+
+    assert_eq!(
+        lines(&buffer),
+        [
+            "spam",
+            "last message repeated 2 times",
+            "other",
+            "last message repeated 1 times"
+        ]
+    );
+}
+
+#[test]
+fn flush_reports_rate_limited_records() {
+    let (mut log, buffer) = log();
+    log.set_rate_limit(Some(2));
+    for _ in 0..5 {
+        write(&mut log, "spam");
+    }
+    log.flush();
+    log.flush();
+
+    assert_eq!(
+        lines(&buffer),
+        [
+            "spam",
+            "spam",
+            "3 records suppressed by the rate limit of 2 per second"
+        ]
+    );
+}
+
+#[test]
+fn shutdown_reports_rate_limited_records() {
+    let (mut log, buffer) = log();
+    log.set_rate_limit(Some(1));
+    write(&mut log, "spam");
+    write(&mut log, "spam");
+    log.shutdown();
+
+    assert_eq!(
+        lines(&buffer),
+        [
+            "spam",
+            "1 records suppressed by the rate limit of 1 per second"
+        ]
+    );
+}
+
+#[test]
+fn disabling_the_rate_limit_reports_suppressed_records() {
+    let (mut log, buffer) = log();
+    log.set_rate_limit(Some(1));
+    write(&mut log, "spam");
+    write(&mut log, "spam");
+    log.set_rate_limit(None);
+    write(&mut log, "spam");
+
+    assert_eq!(
+        lines(&buffer),
+        [
+            "spam",
+            "1 records suppressed by the rate limit of 1 per second",
+            "spam"
+        ]
+    );
+}